@@ -4,21 +4,33 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 use crate::s3w::bucket_ops::create_bucket;
 use crate::s3w::cred::client_from_cred;
 use crate::s3w::{ListOptions, SBucket, get_sbucket_from_cred};
+use std::fs;
+use std::path::PathBuf;
 
 const TEST_BUCKET: &str = "unit-test-bucket";
+/// Note: On the same server as `TEST_BUCKET`, but through another endpoint (so, relayed rather than copied server side)
+const TEST_RELAY_BUCKET: &str = "unit-test-relay-bucket";
 
 // region:    --- S3 Support
 
 pub async fn new_test_ss3_bucket() -> Result<SBucket> {
+	new_test_ss3_bucket_at("http://127.0.0.1:9000", TEST_BUCKET).await
+}
+
+pub async fn new_test_ss3_relay_bucket() -> Result<SBucket> {
+	new_test_ss3_bucket_at("http://localhost:9000", TEST_RELAY_BUCKET).await
+}
+
+async fn new_test_ss3_bucket_at(endpoint: &str, bucket: &str) -> Result<SBucket> {
 	let cred = crate::s3w::cred::AwsCred {
 		key_id: "minio".to_string(),
 		key_secret: "miniominio".to_string(),
 		region: None,
-		endpoint: Some("http://127.0.0.1:9000".to_string()),
+		endpoint: Some(endpoint.to_string()),
 	};
 
 	let client = client_from_cred(cred.clone())?;
-	let res = create_bucket(&client, TEST_BUCKET).await;
+	let res = create_bucket(&client, bucket).await;
 	if let Err(err) = res {
 		match err {
 			crate::Error::AwsSdkErrorWrapper { code, message } => {
@@ -31,7 +43,7 @@ pub async fn new_test_ss3_bucket() -> Result<SBucket> {
 		// println!("Error while new_test_ss3_bucket create_bucket: {:?}", err);
 	}
 
-	let sbucket = get_sbucket_from_cred(cred, TEST_BUCKET).await?;
+	let sbucket = get_sbucket_from_cred(cred, bucket).await?;
 
 	Ok(sbucket)
 }
@@ -44,3 +56,15 @@ pub async fn delete_s3_folder(sbucket: &SBucket, s3_key: &str) -> Result<()> {
 }
 
 // endregion: --- S3 Support
+
+// region:    --- File Support
+
+/// Create (or overwrite) the `name` file in `test_dir` (created if needed), and return its path.
+pub fn create_tmp_file(test_dir: &str, name: &str, content: impl AsRef<[u8]>) -> Result<PathBuf> {
+	fs::create_dir_all(test_dir)?;
+	let file = PathBuf::from(test_dir).join(name);
+	fs::write(&file, content)?;
+	Ok(file)
+}
+
+// endregion: --- File Support
@@ -0,0 +1,55 @@
+use super::CompressMode;
+use crate::_test_support::create_tmp_file;
+use std::fs;
+use std::io::Read;
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+const TEST_COMPRESS_BASE_DIR: &str = "./tests-data/.tmp/test-compress/";
+
+#[test]
+fn test_compress_file_gzip_br() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = "function hello() { return 'hello'; }\n".repeat(200);
+	let fx_file = create_tmp_file(TEST_COMPRESS_BASE_DIR, "test_compress_file_gzip_br.js", content.as_bytes())?;
+
+	// -- Exec & Check - gzip
+	let gz_file = CompressMode::Gzip.compress_file(&fx_file)?.ok_or("Should compress")?;
+	let gz_bytes = fs::read(gz_file.path())?;
+	assert!(gz_bytes.len() < content.len());
+	let mut decoded = String::new();
+	flate2::read::GzDecoder::new(gz_bytes.as_slice()).read_to_string(&mut decoded)?;
+	assert_eq!(decoded, content);
+	// deterministic, so same etag for the same file
+	let gz_file_2 = CompressMode::Gzip.compress_file(&fx_file)?.ok_or("Should compress")?;
+	assert_eq!(fs::read(gz_file_2.path())?, gz_bytes);
+
+	// -- Exec & Check - br
+	let br_file = CompressMode::Br.compress_file(&fx_file)?.ok_or("Should compress")?;
+	let br_bytes = fs::read(br_file.path())?;
+	let mut decoded = String::new();
+	brotli::Decompressor::new(br_bytes.as_slice(), 4096).read_to_string(&mut decoded)?;
+	assert_eq!(decoded, content);
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_compress_file_not_smaller() -> Result<()> {
+	// -- Setup & Fixtures
+	// a tiny file (the compression headers are bigger than the content)
+	let fx_file = create_tmp_file(TEST_COMPRESS_BASE_DIR, "test_compress_file_not_smaller.bin", b"ab")?;
+
+	// -- Exec & Check
+	assert!(CompressMode::Gzip.compress_file(&fx_file)?.is_none());
+	assert!(CompressMode::Br.compress_file(&fx_file)?.is_none());
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::_test_support::{delete_s3_folder, new_test_ss3_bucket, new_test_ss3_relay_bucket};
+use crate::s3w::{CpOptions, OverMode};
+use aws_sdk_s3::primitives::ByteStream;
+use std::collections::HashMap;
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+#[tokio::test]
+async fn test_cp_copy_relay_keeps_headers() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_s3_folder = "test_cp_copy_relay_keeps_headers";
+	let fx_key = format!("{fx_s3_folder}/file.txt");
+	let src_sbucket = new_test_ss3_bucket().await?;
+	let dst_sbucket = new_test_ss3_relay_bucket().await?;
+	delete_s3_folder(&dst_sbucket, fx_s3_folder).await?;
+	src_sbucket
+		.client
+		.put_object()
+		.bucket(&src_sbucket.name)
+		.key(&fx_key)
+		.content_type("text/plain")
+		.content_encoding("gzip")
+		.cache_control("public, max-age=31536000")
+		.content_disposition("attachment")
+		.content_language("en-US")
+		.set_metadata(Some(HashMap::from([("release".to_string(), "1.2.0".to_string())])))
+		.body(ByteStream::from_static(b"hello"))
+		.send()
+		.await?;
+	let opts = CpOptions {
+		over: OverMode::Write,
+		..Default::default()
+	};
+
+	// -- Exec
+	dst_sbucket
+		.copy_path(&src_sbucket, &fx_key, &format!("{fx_s3_folder}/"), opts)
+		.await?;
+
+	// -- Check
+	let head = dst_sbucket
+		.client
+		.head_object()
+		.bucket(&dst_sbucket.name)
+		.key(&fx_key)
+		.send()
+		.await?;
+	assert_eq!(head.content_type(), Some("text/plain"));
+	assert_eq!(head.content_encoding(), Some("gzip"));
+	assert_eq!(head.cache_control(), Some("public, max-age=31536000"));
+	assert_eq!(head.content_disposition(), Some("attachment"));
+	assert_eq!(head.content_language(), Some("en-US"));
+	assert_eq!(head.metadata().and_then(|m| m.get("release")).map(|v| v.as_str()), Some("1.2.0"));
+
+	// -- Clean
+	delete_s3_folder(&src_sbucket, fx_s3_folder).await?;
+	delete_s3_folder(&dst_sbucket, fx_s3_folder).await?;
+
+	Ok(())
+}
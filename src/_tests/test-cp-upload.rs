@@ -1,7 +1,10 @@
 //! Unit test are not fully implemented yet
 
 use crate::_test_support::{delete_s3_folder, new_test_ss3_bucket};
-use crate::s3w::{CpOptions, ListOptions, OverMode};
+use crate::s3w::{CpOptions, ListOptions, ObjectHeaders, ObjectSse, OverMode};
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use aws_sdk_s3::types::ServerSideEncryption;
+use std::collections::HashMap;
 
 pub type Result<T> = core::result::Result<T, Error>;
 pub type Error = Box<dyn std::error::Error>; // For early dev.
@@ -73,3 +76,78 @@ async fn test_cp_write_etag_dir() -> Result<()> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_cp_upload_object_headers() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_s3_folder = "test_cp_upload_object_headers";
+	let sbucket = new_test_ss3_bucket().await?;
+	let opts = CpOptions {
+		over: OverMode::Write,
+		headers: ObjectHeaders {
+			cache_control: Some("public, max-age=31536000".to_string()),
+			content_disposition: Some("attachment".to_string()),
+			content_language: Some("en-US".to_string()),
+			expires: Some(DateTime::from_str("2030-01-31T10:00:00Z", DateTimeFormat::DateTime)?),
+			metadata: Some(HashMap::from([("release".to_string(), "1.2.0".to_string())])),
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	delete_s3_folder(&sbucket, fx_s3_folder).await?;
+
+	// -- Exec
+	sbucket.upload_path(FILE_FIXTURE_FILE_02, fx_s3_folder, opts).await?;
+
+	// -- Check
+	let head = sbucket
+		.client
+		.head_object()
+		.bucket(&sbucket.name)
+		.key(format!("{fx_s3_folder}/sub-file-01.txt"))
+		.send()
+		.await?;
+	assert_eq!(head.cache_control(), Some("public, max-age=31536000"));
+	assert_eq!(head.content_disposition(), Some("attachment"));
+	assert_eq!(head.content_language(), Some("en-US"));
+	assert_eq!(head.metadata().and_then(|m| m.get("release")).map(|v| v.as_str()), Some("1.2.0"));
+
+	// -- Clean
+	delete_s3_folder(&sbucket, fx_s3_folder).await?;
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_cp_upload_sse_kms() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_s3_folder = "test_cp_upload_sse_kms";
+	let sbucket = new_test_ss3_bucket().await?;
+	let opts = CpOptions {
+		over: OverMode::Write,
+		sse: ObjectSse::new(None, Some("test-kms-key".to_string()), None)?,
+		..Default::default()
+	};
+
+	delete_s3_folder(&sbucket, fx_s3_folder).await?;
+
+	// -- Exec
+	sbucket.upload_path(FILE_FIXTURE_FILE_02, fx_s3_folder, opts).await?;
+
+	// -- Check
+	let head = sbucket
+		.client
+		.head_object()
+		.bucket(&sbucket.name)
+		.key(format!("{fx_s3_folder}/sub-file-01.txt"))
+		.send()
+		.await?;
+	assert_eq!(head.server_side_encryption(), Some(&ServerSideEncryption::AwsKms));
+	assert_eq!(head.ssekms_key_id(), Some("test-kms-key"));
+
+	// -- Clean
+	delete_s3_folder(&sbucket, fx_s3_folder).await?;
+
+	Ok(())
+}
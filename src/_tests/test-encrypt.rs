@@ -0,0 +1,163 @@
+use super::{CHUNK_SIZE, Decryption, EncryptKey, META_SIZE, TAG_LEN};
+use crate::_test_support::create_tmp_file;
+use crate::Error as SS3Error;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+const TEST_ENCRYPT_BASE_DIR: &str = "./tests-data/.tmp/test-encrypt/";
+
+/// base64 of the 32 bytes 0..32
+const FX_KEY_B64: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+#[test]
+fn test_encrypt_key_from_file() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_raw_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, "raw.key", (0..32).collect::<Vec<u8>>())?;
+	let fx_b64_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, "b64.key", format!("{FX_KEY_B64}\n").as_bytes())?;
+	let fx_short_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, "short.key", b"too short")?;
+
+	// -- Exec
+	let from_raw_file = EncryptKey::from_file(&fx_raw_file)?;
+	let from_b64_file = EncryptKey::from_file(&fx_b64_file)?;
+	let from_short_file = EncryptKey::from_file(&fx_short_file);
+
+	// -- Check
+	assert_eq!(from_raw_file.key, from_b64_file.key);
+	assert!(matches!(from_short_file, Err(SS3Error::InvalidKeyFile(_))));
+	assert!(!format!("{from_raw_file:?}").contains(FX_KEY_B64));
+
+	// -- Clean
+	for file in [fx_raw_file, fx_b64_file, fx_short_file] {
+		fs::remove_file(file)?;
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_encrypt_decrypt_file_round_trip() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_key = fx_encrypt_key();
+	// Note: Multiple chunks, with a smaller last one, and an empty file (still one chunk)
+	let fx_contents: [Vec<u8>; 3] = [
+		(0..CHUNK_SIZE * 2 + 1000).map(|i| (i % 251) as u8).collect(),
+		b"hello world".to_vec(),
+		Vec::new(),
+	];
+
+	for (i, fx_content) in fx_contents.iter().enumerate() {
+		let fx_src_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, &format!("round-trip-{i}.bin"), fx_content)?;
+		let dst_file = PathBuf::from(TEST_ENCRYPT_BASE_DIR).join(format!("round-trip-{i}.out"));
+
+		// -- Exec
+		let (encrypted_file, metadata) = fx_key.encrypt_file(&fx_src_file)?;
+		decrypt(&fx_key, encrypted_file.path(), &metadata, &dst_file)?;
+
+		// -- Check
+		let encrypted = fs::read(encrypted_file.path())?;
+		let chunk_count = fx_content.len().div_ceil(CHUNK_SIZE).max(1);
+		assert_eq!(encrypted.len(), fx_content.len() + chunk_count * TAG_LEN);
+		if !fx_content.is_empty() {
+			assert_ne!(&encrypted[..fx_content.len()], fx_content.as_slice());
+		}
+		assert_eq!(&fs::read(&dst_file)?, fx_content);
+
+		// -- Clean
+		fs::remove_file(fx_src_file)?;
+		fs::remove_file(dst_file)?;
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_encrypt_decrypt_file_tampered() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_key = fx_encrypt_key();
+	let fx_content: Vec<u8> = (0..CHUNK_SIZE * 2).map(|i| (i % 251) as u8).collect();
+	let fx_src_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, "tampered.bin", &fx_content)?;
+	let (encrypted_file, metadata) = fx_key.encrypt_file(&fx_src_file)?;
+	let encrypted = fs::read(encrypted_file.path())?;
+	let dst_file = PathBuf::from(TEST_ENCRYPT_BASE_DIR).join("tampered.out");
+
+	// -- Exec & Check - a modified byte
+	let mut modified = encrypted.clone();
+	modified[CHUNK_SIZE + 10] ^= 1;
+	let res = decrypt_content(&fx_key, &modified, &metadata, &dst_file);
+	assert!(matches!(res, Err(SS3Error::ObjectDecryptFailed { .. })), "modified byte: {res:?}");
+
+	// -- Exec & Check - swapped chunks
+	let chunk_len = CHUNK_SIZE + TAG_LEN;
+	let swapped = [&encrypted[chunk_len..], &encrypted[..chunk_len]].concat();
+	let res = decrypt_content(&fx_key, &swapped, &metadata, &dst_file);
+	assert!(matches!(res, Err(SS3Error::ObjectDecryptFailed { .. })), "swapped chunks: {res:?}");
+
+	// -- Exec & Check - truncated to its first chunk, with the metadata size to match
+	let mut truncated_metadata = metadata.clone();
+	truncated_metadata.insert(META_SIZE.to_string(), CHUNK_SIZE.to_string());
+	let res = decrypt_content(&fx_key, &encrypted[..chunk_len], &truncated_metadata, &dst_file);
+	assert!(matches!(res, Err(SS3Error::ObjectDecryptFailed { .. })), "truncated: {res:?}");
+
+	// -- Exec & Check - another key
+	let other_key = EncryptKey { key: [7; 32] };
+	let res = decrypt_content(&other_key, &encrypted, &metadata, &dst_file);
+	assert!(matches!(res, Err(SS3Error::ObjectDecryptFailed { .. })), "other key: {res:?}");
+
+	// -- Check - the destination file is never written
+	assert!(!dst_file.exists(), "Should not write the destination file");
+
+	// -- Clean
+	fs::remove_file(fx_src_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_encrypt_decryption_for_object() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_key = fx_encrypt_key();
+	let fx_src_file = create_tmp_file(TEST_ENCRYPT_BASE_DIR, "for-object.txt", b"hello")?;
+	let (_encrypted_file, metadata) = fx_key.encrypt_file(&fx_src_file)?;
+	let dst_file = PathBuf::from(TEST_ENCRYPT_BASE_DIR).join("for-object.out");
+	let plain_metadata = HashMap::from([("release".to_string(), "1.2.0".to_string())]);
+
+	// -- Exec
+	let plain = Decryption::for_object("plain.txt", Some(&plain_metadata), Some(&fx_key), &dst_file)?;
+	let no_metadata = Decryption::for_object("plain.txt", None, None, &dst_file)?;
+	let no_key = Decryption::for_object("encrypted.txt", Some(&metadata), None, &dst_file);
+
+	// -- Check
+	assert!(plain.is_none());
+	assert!(no_metadata.is_none());
+	assert!(matches!(no_key, Err(SS3Error::ObjectEncryptKeyRequired { .. })));
+
+	// -- Clean
+	fs::remove_file(fx_src_file)?;
+
+	Ok(())
+}
+
+// region:    --- Support
+
+fn fx_encrypt_key() -> EncryptKey {
+	EncryptKey {
+		key: core::array::from_fn(|i| i as u8),
+	}
+}
+
+/// Decrypt as a download does (the encrypted content copied to the decryption temp file)
+fn decrypt(key: &EncryptKey, encrypted_file: &Path, metadata: &HashMap<String, String>, dst_file: &Path) -> crate::Result<()> {
+	decrypt_content(key, &fs::read(encrypted_file)?, metadata, dst_file)
+}
+
+fn decrypt_content(key: &EncryptKey, encrypted: &[u8], metadata: &HashMap<String, String>, dst_file: &Path) -> crate::Result<()> {
+	let decryption = Decryption::for_object("fx-key", Some(metadata), Some(key), dst_file)?.ok_or("Should be encrypted")?;
+	fs::write(decryption.encrypted_file(), encrypted)?;
+	decryption.decrypt_to("fx-key", dst_file)
+}
+
+// endregion: --- Support
@@ -0,0 +1,106 @@
+use super::HeaderRules;
+use crate::_test_support::create_tmp_file;
+use crate::s3w::ObjectHeaders;
+use aws_sdk_s3::types::StorageClass;
+use std::fs;
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+const TEST_HEADER_RULES_BASE_DIR: &str = "./tests-data/.tmp/test-header-rules/";
+
+const FX_RULES: &str = r#"
+[[rules]]
+glob = "*.html"
+cache-control = "no-cache"
+
+[[rules]]
+glob = "**/assets/**"
+cache-control = "public, max-age=31536000, immutable"
+metadata = { release = "1.2.0" }
+
+[[rules]]
+glob = "**/assets/**/*.js"
+content-type = "text/javascript"
+metadata = { kind = "script" }
+
+[[rules]]
+glob = "**/archives/**"
+storage-class = "glacier_ir"
+"#;
+
+#[test]
+fn test_header_rules_resolve() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_file = create_tmp_file(TEST_HEADER_RULES_BASE_DIR, "test_header_rules_resolve.toml", FX_RULES)?;
+	let fx_headers = ObjectHeaders {
+		cache_control: Some("max-age=300".to_string()),
+		..Default::default()
+	};
+	let rules = HeaderRules::from_file(&fx_file)?.with_noext_ct("text/html".to_string());
+
+	// -- Exec & Check
+	// html rule (over the command line cache control)
+	let (ct, headers) = rules.resolve("www/index.html", None, &fx_headers);
+	assert_eq!(ct, None);
+	assert_eq!(headers.cache_control.as_deref(), Some("no-cache"));
+
+	// assets rules, the later one adding the content type and merging the metadata
+	let (ct, headers) = rules.resolve("www/assets/app.js", None, &fx_headers);
+	assert_eq!(ct.as_deref(), Some("text/javascript"));
+	assert_eq!(headers.cache_control.as_deref(), Some("public, max-age=31536000, immutable"));
+	let metadata = headers.metadata.ok_or("Should have metadata")?;
+	assert_eq!(metadata.get("release").map(|v| v.as_str()), Some("1.2.0"));
+	assert_eq!(metadata.get("kind").map(|v| v.as_str()), Some("script"));
+
+	// no extension rule (--noext-ct)
+	let (ct, _) = rules.resolve("www/about", None, &fx_headers);
+	assert_eq!(ct.as_deref(), Some("text/html"));
+	// but not over the command line content type
+	let (ct, _) = rules.resolve("www/about", Some("text/plain"), &fx_headers);
+	assert_eq!(ct.as_deref(), Some("text/plain"));
+
+	// storage class rule (case insensitive)
+	let (_, headers) = rules.resolve("www/archives/2020.zip", None, &fx_headers);
+	assert_eq!(headers.storage_class, Some(StorageClass::GlacierIr));
+
+	// no match, the command line ones
+	let (ct, headers) = rules.resolve("www/image.png", Some("image/png"), &fx_headers);
+	assert_eq!(ct.as_deref(), Some("image/png"));
+	assert_eq!(headers.cache_control.as_deref(), Some("max-age=300"));
+	assert!(headers.metadata.is_none());
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_header_rules_invalid_file() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_file = create_tmp_file(
+		TEST_HEADER_RULES_BASE_DIR,
+		"test_header_rules_invalid_file.toml",
+		r#"
+[[rules]]
+glob = "*.html"
+cache_control = "no-cache"
+"#,
+	)?;
+
+	// -- Exec
+	let res = HeaderRules::from_file(&fx_file);
+
+	// -- Check
+	let err = res.err().ok_or("Should fail on the unknown 'cache_control' field")?;
+	assert!(
+		err.to_string().contains("cache_control"),
+		"Should name the unknown field. Was: {err}"
+	);
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
@@ -0,0 +1,75 @@
+use super::{compute_md5, compute_multipart_etag, etag_part_count, file_matches_etag};
+use crate::_test_support::create_tmp_file;
+use std::fs;
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+const TEST_MD5_BASE_DIR: &str = "./tests-data/.tmp/test-md5/";
+const MIB: u64 = 1024 * 1024;
+
+// md5 and multipart etags (per part size) of the 12MiB fixture file
+const FX_MD5: &str = "0678042110afa53b12c87ef1e06d2654";
+const FX_ETAG_5MIB: &str = "7df28755d1a6cc911533a3b50170cf7d-3";
+const FX_ETAG_6MIB: &str = "a1cf7488a217520ca1957f05806b3d0e-2";
+const FX_ETAG_7MIB: &str = "473775b7597860196a0fdcb4c4b1f16f-2";
+const FX_ETAG_8MIB: &str = "a85b9e7ccf4dabf1c2eb720289ce0045-2";
+
+#[test]
+fn test_md5_compute_multipart_etag() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_file = create_tmp_file(TEST_MD5_BASE_DIR, "test_md5_compute_multipart_etag.bin", fx_content())?;
+
+	// -- Exec & Check
+	assert_eq!(compute_md5(&fx_file)?, FX_MD5);
+	assert_eq!(compute_multipart_etag(&fx_file, 5 * MIB)?, FX_ETAG_5MIB);
+	assert_eq!(compute_multipart_etag(&fx_file, 8 * MIB)?, FX_ETAG_8MIB);
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_md5_file_matches_etag() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_file = create_tmp_file(TEST_MD5_BASE_DIR, "test_md5_file_matches_etag.bin", fx_content())?;
+
+	// -- Exec & Check
+	// simple etag
+	assert!(file_matches_etag(&fx_file, FX_MD5, &[])?);
+	// common part sizes
+	assert!(file_matches_etag(&fx_file, FX_ETAG_5MIB, &[])?);
+	assert!(file_matches_etag(&fx_file, FX_ETAG_8MIB, &[])?);
+	// inferred from the part count (12MiB / 2)
+	assert!(file_matches_etag(&fx_file, FX_ETAG_6MIB, &[])?);
+	// not common nor inferred, so only when given
+	assert!(!file_matches_etag(&fx_file, FX_ETAG_7MIB, &[])?);
+	assert!(file_matches_etag(&fx_file, FX_ETAG_7MIB, &[7 * MIB])?);
+	// wrong part count
+	assert!(!file_matches_etag(&fx_file, "a85b9e7ccf4dabf1c2eb720289ce0045-3", &[])?);
+
+	// -- Clean
+	fs::remove_file(fx_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_md5_etag_part_count() -> Result<()> {
+	assert_eq!(etag_part_count(FX_ETAG_5MIB), Some(3));
+	assert_eq!(etag_part_count(FX_MD5), None);
+	assert_eq!(etag_part_count("abc-"), None);
+
+	Ok(())
+}
+
+// region:    --- Support
+
+/// The 12MiB deterministic content (bytes `i % 251`) of the fixture files.
+fn fx_content() -> Vec<u8> {
+	(0..12 * MIB).map(|i| (i % 251) as u8).collect()
+}
+
+// endregion: --- Support
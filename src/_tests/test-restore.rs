@@ -0,0 +1,27 @@
+use super::RestoreStatus;
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+#[test]
+fn test_restore_status_from_header() -> Result<()> {
+	// -- Exec & Check
+	// restoring
+	let status = RestoreStatus::from_header(r#"ongoing-request="true""#);
+	assert_eq!(status, Some(RestoreStatus::InProgress));
+
+	// restored, with its expiry date
+	let status = RestoreStatus::from_header(r#"ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT""#);
+	let expiry = DateTime::from_str("2012-12-21T00:00:00Z", DateTimeFormat::DateTime)?;
+	assert_eq!(status, Some(RestoreStatus::Restored { expiry: Some(expiry) }));
+	assert_eq!(
+		status.ok_or("Should have a status")?.to_string(),
+		"restored until 2012-12-21T00:00:00Z"
+	);
+
+	// invalid
+	assert_eq!(RestoreStatus::from_header("something else"), None);
+
+	Ok(())
+}
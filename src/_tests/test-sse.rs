@@ -0,0 +1,91 @@
+use super::{ObjectSse, SseCustomerKey, head_etag_is_md5};
+use crate::_test_support::create_tmp_file;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::types::ServerSideEncryption;
+use std::fs;
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+const TEST_SSE_BASE_DIR: &str = "./tests-data/.tmp/test-sse/";
+
+/// base64 of the 32 bytes 0..32
+const FX_KEY_B64: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+#[test]
+fn test_sse_customer_key_from_arg() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_raw_file = create_tmp_file(TEST_SSE_BASE_DIR, "raw.key", (0..32).collect::<Vec<u8>>())?;
+	let fx_b64_file = create_tmp_file(TEST_SSE_BASE_DIR, "b64.key", format!("{FX_KEY_B64}\n"))?;
+
+	// -- Exec
+	let from_b64 = SseCustomerKey::from_arg(FX_KEY_B64)?;
+	let from_raw_file = SseCustomerKey::from_arg(&fx_raw_file.to_string_lossy())?;
+	let from_b64_file = SseCustomerKey::from_arg(&fx_b64_file.to_string_lossy())?;
+
+	// -- Check
+	assert_eq!(from_b64.key_b64, FX_KEY_B64);
+	assert_eq!(from_raw_file.key_b64, FX_KEY_B64);
+	assert_eq!(from_b64_file.key_b64, FX_KEY_B64);
+	assert_eq!(from_b64.key_md5_b64, from_raw_file.key_md5_b64);
+
+	// -- Clean
+	fs::remove_file(fx_raw_file)?;
+	fs::remove_file(fx_b64_file)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_sse_customer_key_invalid_and_redacted() -> Result<()> {
+	// -- Exec & Check - not 256 bits (the error does not contain the key)
+	let short_key = "AAECAwQFBgcICQoLDA0ODw==";
+	let err = SseCustomerKey::from_arg(short_key).err().ok_or("Should fail on a 128-bit key")?;
+	assert!(err.to_string().contains("256 bits"), "Wrong error: {err}");
+	assert!(!err.to_string().contains(short_key), "Should not contain the key: {err}");
+
+	// -- Exec & Check - the debug output redacts the key
+	let opts = ObjectSse::new(None, None, Some(SseCustomerKey::from_arg(FX_KEY_B64)?))?;
+	let debug = format!("{opts:?}");
+	assert!(debug.contains("REDACTED"), "Should be redacted: {debug}");
+	assert!(!debug.contains(FX_KEY_B64), "Should not contain the key: {debug}");
+
+	Ok(())
+}
+
+#[test]
+fn test_sse_object_sse_new() -> Result<()> {
+	// -- Exec & Check - the KMS key id implies aws:kms
+	let sse = ObjectSse::new(None, Some("my-key".to_string()), None)?;
+	assert_eq!(sse.encryption, Some(ServerSideEncryption::AwsKms));
+
+	// -- Exec & Check - SSE-S3
+	let sse = ObjectSse::new(Some("AES256"), None, None)?;
+	assert_eq!(sse.encryption, Some(ServerSideEncryption::Aes256));
+
+	// -- Exec & Check - invalid combination
+	assert!(ObjectSse::new(Some("AES256"), Some("my-key".to_string()), None).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_sse_head_etag_is_md5() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_plain = HeadObjectOutput::builder().build();
+	let fx_sse_s3 = HeadObjectOutput::builder()
+		.server_side_encryption(ServerSideEncryption::Aes256)
+		.build();
+	let fx_sse_kms = HeadObjectOutput::builder()
+		.server_side_encryption(ServerSideEncryption::AwsKms)
+		.build();
+	let fx_sse_c = HeadObjectOutput::builder().sse_customer_algorithm("AES256").build();
+
+	// -- Exec & Check - decided by the object encryption (e.g., a bucket default SSE-KMS)
+	assert!(head_etag_is_md5(&fx_plain));
+	assert!(head_etag_is_md5(&fx_sse_s3));
+	assert!(!head_etag_is_md5(&fx_sse_kms));
+	assert!(!head_etag_is_md5(&fx_sse_c));
+
+	Ok(())
+}
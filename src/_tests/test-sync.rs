@@ -0,0 +1,52 @@
+use super::{SyncEntry, is_changed};
+use crate::s3w::{MultipartOptions, SItem, SItemType};
+use aws_sdk_s3::primitives::DateTime;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+#[test]
+fn test_sync_is_changed_sse_kms_etags() -> Result<()> {
+	// -- Setup & Fixtures
+	// Note: Same content, but the SSE-KMS etags are not md5 (and different per upload), with the destination more recent
+	let fx_src_modified = SystemTime::now() - Duration::from_secs(3600);
+	let fx_src_object = SyncEntry::Object(fx_sitem("0123456789abcdef0123456789abcdef", fx_src_modified, Some(false)));
+	let fx_src_file = SyncEntry::File {
+		file: PathBuf::from("./tests-data/.tmp/test-sync/not-read.txt"),
+		size: 5,
+		modified: Some(fx_src_modified),
+	};
+	let fx_dst_kms = SyncEntry::Object(fx_sitem("fedcba9876543210fedcba9876543210", SystemTime::now(), Some(false)));
+	let fx_dst_listed = SyncEntry::Object(fx_sitem("fedcba9876543210fedcba9876543210", SystemTime::now(), None));
+	let multipart = MultipartOptions::default();
+
+	// -- Exec & Check - decided by the modified times when the head tells SSE-KMS
+	assert!(!is_changed(&fx_src_object, &fx_dst_kms, &multipart));
+	assert!(!is_changed(&fx_src_file, &fx_dst_kms, &multipart));
+
+	// -- Exec & Check - different etags of listed objects are changed (until confirmed by their head)
+	let fx_src_listed = SyncEntry::Object(fx_sitem("0123456789abcdef0123456789abcdef", fx_src_modified, None));
+	assert!(is_changed(&fx_src_listed, &fx_dst_listed, &multipart));
+
+	Ok(())
+}
+
+// region:    --- Support
+
+fn fx_sitem(etag: &str, modified: SystemTime, etag_is_md5: Option<bool>) -> SItem {
+	SItem {
+		typ: SItemType::Object,
+		key: "some-text.txt".to_string(),
+		etag: Some(etag.to_string()),
+		size: 5,
+		last_modified: Some(DateTime::from(modified)),
+		storage_class: None,
+		owner: None,
+		restore: None,
+		etag_is_md5,
+	}
+}
+
+// endregion: --- Support
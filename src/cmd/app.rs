@@ -11,17 +11,50 @@ pub const ARG_INCLUDE: &str = "include";
 pub const ARG_NOEXT_CT: &str = "noext-ct";
 pub const ARG_OVER: &str = "over";
 pub const ARG_SHOW_SKIP: &str = "show-skip";
+pub const ARG_MULTIPART_THRESHOLD: &str = "multipart-threshold";
+pub const ARG_PART_SIZE: &str = "part-size";
+pub const ARG_CONCURRENCY: &str = "concurrency";
+pub const ARG_PART_CONCURRENCY: &str = "part-concurrency";
+pub const ARG_DELETE: &str = "delete";
+pub const ARG_DRY_RUN: &str = "dry-run";
+pub const ARG_CONTENT_TYPE: &str = "content-type";
+pub const ARG_REVERSE: &str = "reverse";
+pub const ARG_LONG: (&str, char) = ("long", 'l');
+pub const ARG_HUMAN: &str = "human";
+pub const ARG_BYTES: &str = "bytes";
+pub const ARG_OUTPUT: &str = "output";
+pub const ARG_CACHE_CONTROL: &str = "cache-control";
+pub const ARG_CONTENT_ENCODING: &str = "content-encoding";
+pub const ARG_CONTENT_DISPOSITION: &str = "content-disposition";
+pub const ARG_CONTENT_LANGUAGE: &str = "content-language";
+pub const ARG_EXPIRES: &str = "expires";
+pub const ARG_METADATA: &str = "metadata";
+pub const ARG_STORAGE_CLASS: &str = "storage-class";
+pub const ARG_HEADERS_FILE: &str = "headers-file";
+pub const ARG_COMPRESS: &str = "compress";
+pub const ARG_ENCRYPT_KEY_FILE: &str = "encrypt-key-file";
+pub const ARG_SSE: &str = "sse";
+pub const ARG_SSE_KMS_KEY_ID: &str = "sse-kms-key-id";
+pub const ARG_SSE_C_KEY: &str = "sse-c-key";
+pub const ARG_DAYS: &str = "days";
+pub const ARG_TIER: &str = "tier";
+pub const ARG_WAIT: &str = "wait";
 
 pub fn cmd_app() -> Command {
 	Command::new("ss3")
 		.version(crate_version!())
 		.args(args_region_profile())
+		.arg(arg_dry_run())
+		.arg(arg_output())
 		.subcommand(sub_ls())
 		.subcommand(sub_cp())
+		.subcommand(sub_mv())
 		.subcommand(sub_rm())
 		.subcommand(sub_mb())
 		.subcommand(sub_rb())
 		.subcommand(sub_clean())
+		.subcommand(sub_sync())
+		.subcommand(sub_restore())
 }
 
 // region:    --- Sub Commands
@@ -45,6 +78,26 @@ fn sub_ls() -> Command {
 				.long("info-only")
 				.help("Display only info of the listing (total files, total size, total size per extension)"),
 		)
+		.arg(
+			Arg::new(ARG_LONG.0)
+				.action(ArgAction::SetTrue)
+				.short(ARG_LONG.1)
+				.long(ARG_LONG.0)
+				.help("Long listing format (last modified, size, storage class, etag, owner, key)"),
+		)
+		.arg(
+			Arg::new(ARG_HUMAN)
+				.action(ArgAction::SetTrue)
+				.long(ARG_HUMAN)
+				.conflicts_with(ARG_BYTES)
+				.help("Display the sizes in human readable format, e.g., 1.2M (default)"),
+		)
+		.arg(
+			Arg::new(ARG_BYTES)
+				.action(ArgAction::SetTrue)
+				.long(ARG_BYTES)
+				.help("Display the sizes in bytes"),
+		)
 }
 
 fn sub_mb() -> Command {
@@ -59,11 +112,18 @@ fn sub_rb() -> Command {
 		.about("Delete an S3 bucket. e.g., `ss3 rb ss3://my-bucket`")
 		.args(args_region_profile())
 		.arg(arg_path_1())
+		.arg(
+			Arg::new(ARG_FORCE)
+				.num_args(0)
+				.long(ARG_FORCE)
+				.action(ArgAction::SetTrue)
+				.help("Empty the bucket first (all objects, versions, delete markers, and in progress multipart uploads), after a prompt"),
+		)
 }
 
 fn sub_cp() -> Command {
 	Command::new("cp")
-		.about("Copy from s3 url / file path to s3 url / file path")
+		.about("Copy from s3 url / file path / '-' (stdin) to s3 url / file path / '-' (stdout)")
 		.args(args_region_profile())
 		.arg(arg_path_1())
 		.arg(arg_path_2())
@@ -71,12 +131,69 @@ fn sub_cp() -> Command {
 		.arg(arg_exlude())
 		.arg(arg_recursive())
 		.arg(arg_noext_ct())
-		.arg(arg_show_skip())
+		.arg(arg_content_type())
+		.args(args_object_headers())
+		.arg(arg_headers_file())
+		.args(args_sse())
 		.arg(
-			Arg::new(ARG_OVER)
-				.long("over")
+			Arg::new(ARG_COMPRESS)
 				.num_args(1)
-				.help("Overwrite mode. Default 'skip'. Can be 'skip', 'etag', 'write', 'fail'"),
+				.long(ARG_COMPRESS)
+				.value_parser(["gzip", "br"])
+				.help("Compress the uploaded files (with their Content-Encoding), when it reduces their size. e.g., --compress gzip -i '*.js'"),
+		)
+		.arg(
+			Arg::new(ARG_ENCRYPT_KEY_FILE)
+				.num_args(1)
+				.long(ARG_ENCRYPT_KEY_FILE)
+				.help("Client side encrypt the uploaded files (AES-256-GCM) with the 256-bit key of this file (raw or base64), and decrypt the encrypted downloads"),
+		)
+		.arg(arg_show_skip())
+		.args(args_multipart())
+		.arg(arg_concurrency())
+		.arg(arg_over())
+}
+
+fn sub_mv() -> Command {
+	Command::new("mv")
+		.about("Move from s3 url / file path to s3 url / file path (each source is deleted once its destination is verified)")
+		.args(args_region_profile())
+		.arg(arg_path_1())
+		.arg(arg_path_2())
+		.arg(arg_include())
+		.arg(arg_exlude())
+		.arg(arg_recursive())
+		.arg(arg_noext_ct())
+		.arg(arg_content_type())
+		.args(args_object_headers())
+		.arg(arg_headers_file())
+		.args(args_sse())
+		.arg(arg_show_skip())
+		.args(args_multipart())
+		.arg(arg_concurrency())
+		.arg(arg_over())
+}
+
+fn sub_sync() -> Command {
+	Command::new("sync")
+		.about("Sync a directory/prefix to another one (local to s3, s3 to local, s3 to s3), transferring only the new and changed files")
+		.args(args_region_profile())
+		.arg(arg_path_1())
+		.arg(arg_path_2())
+		.arg(arg_include())
+		.arg(arg_exlude())
+		.arg(arg_noext_ct())
+		.arg(arg_headers_file())
+		.args(args_sse())
+		.arg(arg_show_skip())
+		.args(args_multipart())
+		.arg(arg_concurrency())
+		.arg(
+			Arg::new(ARG_DELETE)
+				.num_args(0)
+				.long(ARG_DELETE)
+				.action(ArgAction::SetTrue)
+				.help("Delete the destination files/objects that are not in the source (excluded ones are kept)"),
 		)
 }
 
@@ -88,13 +205,58 @@ fn sub_clean() -> Command {
 		.arg(arg_path_2())
 		.arg(arg_force())
 		.arg(arg_recursive())
+		.arg(arg_include())
+		.arg(arg_exlude())
+		.arg(
+			Arg::new(ARG_REVERSE)
+				.num_args(0)
+				.long(ARG_REVERSE)
+				.action(ArgAction::SetTrue)
+				.help("Remove the local files for which there is no s3 object (rather than the s3 objects without local file)"),
+		)
 }
 
 fn sub_rm() -> Command {
 	Command::new("rm")
-		.about("Delete a S3 object by it's URL")
+		.about("Delete a S3 object by it's URL, or all the objects of a prefix with '-r' (by batches of 1000)")
 		.args(args_region_profile())
 		.arg(arg_path_1())
+		.arg(arg_recursive())
+		.arg(arg_include())
+		.arg(arg_exlude())
+		.arg(arg_force())
+}
+
+fn sub_restore() -> Command {
+	Command::new("restore")
+		.about("Restore an archived S3 object (GLACIER, DEEP_ARCHIVE) for a number of days, or all the archived objects of a prefix with '-r'")
+		.args(args_region_profile())
+		.arg(arg_path_1())
+		.arg(arg_recursive())
+		.arg(arg_include())
+		.arg(arg_exlude())
+		.arg(
+			Arg::new(ARG_DAYS)
+				.num_args(1)
+				.long(ARG_DAYS)
+				.value_parser(clap::value_parser!(i32).range(1..))
+				.help("The number of days the restored copies are kept (default 1)"),
+		)
+		.arg(
+			Arg::new(ARG_TIER)
+				.num_args(1)
+				.long(ARG_TIER)
+				.value_parser(["Standard", "Bulk", "Expedited"])
+				.ignore_case(true)
+				.help("The restore tier (default Standard). e.g., --tier Bulk"),
+		)
+		.arg(
+			Arg::new(ARG_WAIT)
+				.num_args(0)
+				.long(ARG_WAIT)
+				.action(ArgAction::SetTrue)
+				.help("Wait until the objects are restored (checking their restore status every minute)"),
+		)
 }
 // endregion: --- Sub Commands
 
@@ -134,6 +296,41 @@ fn args_region_profile() -> [Arg; 2] {
 			.help("The region to use for this command (override profile/env region)."),
 	]
 }
+
+/// The Content-Type of the uploads (cp and mv).
+fn arg_content_type() -> Arg {
+	Arg::new(ARG_CONTENT_TYPE)
+		.num_args(1)
+		.long(ARG_CONTENT_TYPE)
+		.help("Content-Type of the uploads, overriding the one from the file extension (default for stdin 'application/octet-stream')")
+}
+
+fn arg_over() -> Arg {
+	Arg::new(ARG_OVER)
+		.long(ARG_OVER)
+		.num_args(1)
+		.value_parser(["skip", "etag", "write", "fail", "size", "newer", "size-mtime"])
+		.help("Overwrite mode. Default 'skip'")
+}
+
+fn arg_output() -> Arg {
+	Arg::new(ARG_OUTPUT)
+		.num_args(1)
+		.long(ARG_OUTPUT)
+		.global(true)
+		.value_parser(["text", "json", "ndjson"])
+		.help("Output format. Default 'text'. 'json' (one array at the end) and 'ndjson' (one record per line) for scripts")
+}
+
+/// Note: Global, so it can be given before or after the sub command.
+fn arg_dry_run() -> Arg {
+	Arg::new(ARG_DRY_RUN)
+		.num_args(0)
+		.long(ARG_DRY_RUN)
+		.global(true)
+		.action(ArgAction::SetTrue)
+		.help("Only report what would be uploaded, downloaded, copied, or deleted (no change is made)")
+}
 // endregion: --- Common Args
 
 // region:    --- Clean
@@ -185,4 +382,91 @@ fn arg_noext_ct() -> Arg {
 		.help("Content-Type when no file extension. e.g., --noext-ct 'html' (alias for 'text/html; charset=UTF-8')")
 }
 
+fn arg_concurrency() -> Arg {
+	Arg::new(ARG_CONCURRENCY)
+		.num_args(1)
+		.long(ARG_CONCURRENCY)
+		.value_parser(clap::value_parser!(usize))
+		.help("Max number of files transferred at the same time. Default 1. e.g., --concurrency 16")
+}
+
+fn args_multipart() -> [Arg; 3] {
+	[
+		Arg::new(ARG_MULTIPART_THRESHOLD)
+			.num_args(1)
+			.long(ARG_MULTIPART_THRESHOLD)
+			.help("Size from which files/objects are transferred by parts (multipart upload, ranged download). Default '8MB'."),
+		Arg::new(ARG_PART_SIZE)
+			.num_args(1)
+			.long(ARG_PART_SIZE)
+			.help("Part size for multipart uploads and ranged downloads (min '5MB', max '5GB'). Default '8MB'. e.g., --part-size 16MB"),
+		Arg::new(ARG_PART_CONCURRENCY)
+			.num_args(1)
+			.long(ARG_PART_CONCURRENCY)
+			.value_parser(clap::value_parser!(usize))
+			.help("Max number of parts of a file transferred at the same time. Default 4. e.g., --part-concurrency 8"),
+	]
+}
+
+fn arg_headers_file() -> Arg {
+	Arg::new(ARG_HEADERS_FILE)
+		.num_args(1)
+		.long(ARG_HEADERS_FILE)
+		.help("TOML file of per glob rules (on the key) of the content type, cache control, content encoding, metadata, and storage class of the uploads")
+}
+
+fn args_object_headers() -> [Arg; 7] {
+	[
+		Arg::new(ARG_CACHE_CONTROL)
+			.num_args(1)
+			.long(ARG_CACHE_CONTROL)
+			.help("Cache-Control of the uploaded/copied objects. e.g., --cache-control 'public, max-age=31536000'"),
+		Arg::new(ARG_CONTENT_ENCODING)
+			.num_args(1)
+			.long(ARG_CONTENT_ENCODING)
+			.help("Content-Encoding of the uploaded/copied objects (as is, the content is not encoded). e.g., --content-encoding gzip"),
+		Arg::new(ARG_CONTENT_DISPOSITION)
+			.num_args(1)
+			.long(ARG_CONTENT_DISPOSITION)
+			.help("Content-Disposition of the uploaded/copied objects. e.g., --content-disposition 'attachment'"),
+		Arg::new(ARG_CONTENT_LANGUAGE)
+			.num_args(1)
+			.long(ARG_CONTENT_LANGUAGE)
+			.help("Content-Language of the uploaded/copied objects. e.g., --content-language 'en-US'"),
+		Arg::new(ARG_EXPIRES)
+			.num_args(1)
+			.long(ARG_EXPIRES)
+			.help("Expires of the uploaded/copied objects, as RFC 3339 or HTTP date. e.g., --expires '2030-01-31T10:00:00Z'"),
+		Arg::new(ARG_METADATA)
+			.num_args(1)
+			.long(ARG_METADATA)
+			.action(ArgAction::Append)
+			.help("User metadata 'key=value' of the uploaded/copied objects (can be repeated). e.g., --metadata 'release=1.2.0'"),
+		Arg::new(ARG_STORAGE_CLASS)
+			.num_args(1)
+			.long(ARG_STORAGE_CLASS)
+			.help("Storage class of the uploaded/copied objects. e.g., --storage-class STANDARD_IA (or GLACIER_IR, INTELLIGENT_TIERING, ...)"),
+	]
+}
+
+fn args_sse() -> [Arg; 3] {
+	[
+		Arg::new(ARG_SSE)
+			.num_args(1)
+			.long(ARG_SSE)
+			.value_parser(["AES256", "aws:kms"])
+			.conflicts_with(ARG_SSE_C_KEY)
+			.help("Server side encryption of the uploaded/copied objects, SSE-S3 (AES256) or SSE-KMS (aws:kms)"),
+		Arg::new(ARG_SSE_KMS_KEY_ID)
+			.num_args(1)
+			.long(ARG_SSE_KMS_KEY_ID)
+			.conflicts_with(ARG_SSE_C_KEY)
+			.help("The KMS key id/arn of SSE-KMS (implies '--sse aws:kms', otherwise the AWS managed key)"),
+		Arg::new(ARG_SSE_C_KEY)
+			.num_args(1)
+			.long(ARG_SSE_C_KEY)
+			.help("SSE-C 256-bit key, base64 encoded or a key file, used for all the requests on the objects (the copy sources included)"),
+	]
+}
+
 // endregion: --- cp Args
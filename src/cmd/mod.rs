@@ -4,19 +4,35 @@
 mod app;
 
 // -- Imports
-use crate::cmd::app::{ARG_FORCE, ARG_REGION, cmd_app};
+use crate::cmd::app::{ARG_CONTENT_TYPE, ARG_DRY_RUN, ARG_FORCE, ARG_REGION, cmd_app};
+use crate::output::{
+	Event, EventStatus, ExtensionInfo, InfoRecord, OutputFormat, Record, emit, flush_output, init_output_format, is_text_output,
+	set_stdout_streamed,
+};
 use crate::prompt::prompt;
 use crate::s3w::{
-	CleanOptions, CpOptions, ListInfo, ListOptions, ListResult, OverMode, RegionProfile, create_bucket, delete_bucket, get_sbucket,
-	list_buckets, new_s3_client,
+	CleanOptions, CompressMode, CpOptions, DEFAULT_RESTORE_DAYS, EncryptKey, HeaderRules, ListInfo, ListOptions, ListResult,
+	MultipartOptions, ObjectHeaders, ObjectSse, OverMode, Progress, RegionProfile, RestoreOptions, RmOptions, SBucket, SItem, SseCustomerKey,
+	SyncOptions, create_bucket, delete_bucket, get_sbucket, list_buckets, new_s3_client, parse_storage_class,
 };
 use crate::spath::{S3Url, SPath};
-use crate::{CT_HTML, CT_TEXT, Error, Result, s};
-use app::{ARG_NOEXT_CT, ARG_OVER, ARG_PATH_1, ARG_PATH_2, ARG_PROFILE, ARG_RECURSIVE};
+use crate::utils::size::parse_size;
+use crate::{CT_HTML, CT_TEXT, Error, Result, f, s};
+use app::{
+	ARG_BYTES, ARG_CACHE_CONTROL, ARG_COMPRESS, ARG_CONCURRENCY, ARG_CONTENT_DISPOSITION, ARG_CONTENT_ENCODING, ARG_CONTENT_LANGUAGE,
+	ARG_DAYS, ARG_DELETE, ARG_ENCRYPT_KEY_FILE, ARG_EXCLUDE, ARG_EXPIRES, ARG_HEADERS_FILE, ARG_INCLUDE, ARG_LONG, ARG_METADATA,
+	ARG_MULTIPART_THRESHOLD, ARG_NOEXT_CT, ARG_OUTPUT, ARG_OVER, ARG_PART_CONCURRENCY, ARG_PART_SIZE, ARG_PATH_1, ARG_PATH_2, ARG_PROFILE,
+	ARG_RECURSIVE, ARG_REVERSE, ARG_SSE, ARG_SSE_C_KEY, ARG_SSE_KMS_KEY_ID, ARG_STORAGE_CLASS, ARG_TIER, ARG_WAIT,
+};
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use aws_sdk_s3::types::Tier;
 use clap::ArgMatches;
 use file_size::fit_4;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
 
 // endregion: --- Modules
 
@@ -42,13 +58,26 @@ pub async fn cmd_run() -> Result<()> {
 
 	let reg_pro = RegionProfile { region, profile };
 
+	init_output_format(get_output_format(&argm)?);
+
+	// Note: The json output is printed at the end, even on failure (with the records of what was done)
+	let res = exec_sub_cmd(reg_pro, &argm).await;
+	flush_output();
+
+	res
+}
+
+async fn exec_sub_cmd(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 	match argm.subcommand() {
 		Some(("ls", sub_cmd)) => exec_ls(reg_pro, sub_cmd).await?,
 		Some(("cp", sub_cmd)) => exec_cp(reg_pro, sub_cmd).await?,
+		Some(("mv", sub_cmd)) => exec_mv(reg_pro, sub_cmd).await?,
 		Some(("rm", sub_cmd)) => exec_rm(reg_pro, sub_cmd).await?,
 		Some(("mb", sub_cmd)) => exec_mb(reg_pro, sub_cmd).await?,
 		Some(("rb", sub_cmd)) => exec_rb(reg_pro, sub_cmd).await?,
 		Some(("clean", sub_cmd)) => exec_clean(reg_pro, sub_cmd).await?,
+		Some(("sync", sub_cmd)) => exec_sync(reg_pro, sub_cmd).await?,
+		Some(("restore", sub_cmd)) => exec_restore(reg_pro, sub_cmd).await?,
 
 		_ => {
 			cmd_app().print_long_help()?;
@@ -77,7 +106,11 @@ async fn exec_ls_buckets(reg_pro: RegionProfile) -> Result<()> {
 	let client = new_s3_client(reg_pro, None).await?;
 	let buckets = list_buckets(&client).await?;
 	for bucket in buckets {
-		println!("{bucket}");
+		if is_text_output() {
+			println!("{bucket}");
+		} else {
+			emit(Record::Bucket { name: bucket });
+		}
 	}
 	Ok(())
 }
@@ -85,7 +118,7 @@ async fn exec_ls_buckets(reg_pro: RegionProfile) -> Result<()> {
 async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches) -> Result<()> {
 	let s3_url = match spath {
 		SPath::S3(s3_url) => s3_url,
-		SPath::File(_) => return Err(Error::CmdInvalid("The 'ls' command requires a S3 url.")),
+		SPath::File(_) | SPath::Std => return Err(Error::CmdInvalid("The 'ls' command requires a S3 url.")),
 	};
 
 	// build the bucket
@@ -93,6 +126,8 @@ async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches
 
 	// build the option (take ownership of the continuation_token)
 	let mut options = ListOptions::from_argm(argm)?;
+	let long = argm.get_flag(ARG_LONG.0);
+	let bytes = argm.get_flag(ARG_BYTES);
 
 	let mut total_objects: i64 = 0;
 	let mut total_size: i64 = 0;
@@ -110,7 +145,7 @@ async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches
 		// -- Execute the bucket.list command
 		let ListResult {
 			prefixes,
-			objects,
+			mut objects,
 			next_continuation_token,
 		} = bucket.list(s3_url.key(), &options).await?;
 
@@ -118,11 +153,22 @@ async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches
 		// Print prefixes (dirs) first
 		// Note: When recursive, the list of prefixes is not given by the aws sdk
 		for item in prefixes.iter() {
-			println!("{}", item.key);
+			if !is_text_output() {
+				emit(item);
+			} else if long {
+				println!("{:19}  {:>10}  {}", "", "PRE", item.key);
+			} else {
+				println!("{}", item.key);
+			}
 		}
 
 		// -- Print objects
-		for item in objects.iter() {
+		for item in objects.iter_mut() {
+			// Note: The restore status of the archived objects is only given by their head
+			if long && item.is_archived() {
+				item.restore = bucket.head_sitem(&item.key).await?.restore;
+			}
+
 			total_objects += 1;
 			total_size += item.size;
 			if let Some(ext_idx) = item.key.rfind('.') {
@@ -132,7 +178,11 @@ async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches
 				val.1 += 1;
 			}
 
-			if show_list {
+			if show_list && !is_text_output() {
+				emit(&*item);
+			} else if show_list && long {
+				print_long_sitem(item, bytes);
+			} else if show_list {
 				println!("{}", item.key);
 			}
 		}
@@ -142,17 +192,30 @@ async fn exec_ls_objects(reg_pro: RegionProfile, spath: SPath, argm: &ArgMatches
 		continuation_token.is_some() // will continue the while loop if not none
 	} {} // this is the way to do `do while` in rust
 
-	if let Some(ListInfo::InfoOnly | ListInfo::WithInfo) = options.info {
+	if let Some(ListInfo::InfoOnly | ListInfo::WithInfo) = options.info
+		&& !is_text_output()
+	{
+		let mut extensions: Vec<ExtensionInfo> = size_per_ext
+			.into_iter()
+			.map(|(ext, (size, count))| ExtensionInfo { ext, size, count })
+			.collect();
+		extensions.sort_by(|a, b| a.ext.cmp(&b.ext));
+		emit(InfoRecord {
+			total_count: total_objects,
+			total_size,
+			extensions,
+		});
+	} else if let Some(ListInfo::InfoOnly | ListInfo::WithInfo) = options.info {
 		println!("\n--- Info:");
 		let mut exts: Vec<(&String, &(Size, Count))> = size_per_ext.iter().map(|e| (e.0, e.1)).collect();
 		exts.sort_by(|a, b| a.0.cmp(b.0));
 		for (ext, (size, count)) in exts.into_iter() {
-			println!("{ext:<5} - size: {:<5} count: {count} ", fit_4(*size as u64))
+			println!("{ext:<5} - size: {:<5} count: {count} ", fmt_size(*size, bytes))
 		}
 
 		println!();
-		let total_size_fit_4 = fit_4(total_size as u64);
-		println!("total size: {total_size_fit_4:5} total count: {total_objects} ");
+		let total_size_fmt = fmt_size(total_size, bytes);
+		println!("total size: {total_size_fmt:5} total count: {total_objects} ");
 	}
 
 	Ok(())
@@ -162,10 +225,23 @@ pub async fn exec_mb(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 	let s3_url = get_s3_url_1(argm)?;
 	let bucket_name = s3_url.bucket();
 
+	if argm.get_flag(ARG_DRY_RUN) {
+		if is_text_output() {
+			println!("Would create bucket: {bucket_name}");
+		} else {
+			emit(Event::new("create_bucket", bucket_name, EventStatus::DryRun));
+		}
+		return Ok(());
+	}
+
 	let client = new_s3_client(reg_pro, Some(bucket_name)).await?;
 	let bucket_created = create_bucket(&client, bucket_name).await?;
 	if let Some(bucket_created) = bucket_created {
-		println!("Bucket Created: {bucket_created}");
+		if is_text_output() {
+			println!("Bucket Created: {bucket_created}");
+		} else {
+			emit(Event::new("create_bucket", bucket_created, EventStatus::Ok));
+		}
 	}
 
 	Ok(())
@@ -180,28 +256,32 @@ pub async fn exec_clean(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()>
 			let bucket = get_sbucket(reg_pro, s3_url.bucket()).await?;
 			let opts = CleanOptions::from_argm(argm)?;
 
-			let keys_to_delete = bucket.list_to_clean(src_path, s3_url.key()).await?;
-			// if not force, we prompt
-			let perform_delete = if !opts.force {
-				println!("Will delete {} s3 objects. List of all keys to be deleted:", keys_to_delete.len());
-				for key in keys_to_delete.iter() {
-					println!("    {key}");
+			if opts.reverse {
+				return exec_clean_reverse(&bucket, &src_path, &s3_url, &opts).await;
+			}
+
+			let sitems_to_delete = bucket.list_to_clean(src_path, s3_url.key(), &opts).await?;
+
+			// if dry run, we just report
+			if opts.dry_run {
+				for sitem in sitems_to_delete.iter() {
+					print_dry_run_delete(&bucket.s3_url(&sitem.key), sitem.size);
 				}
-				let response = prompt(&format!(
-					"\n{} s3 objects found to delete ('YES' to delete): ",
-					keys_to_delete.len()
-				))?;
-				response == "YES"
-			} else {
-				true
-			};
+				return Ok(());
+			}
+
+			if sitems_to_delete.is_empty() {
+				print_msg(f!("No s3 objects to clean under {s3_url}"));
+				return Ok(());
+			}
+
+			let keys_to_delete: Vec<&str> = sitems_to_delete.iter().map(|sitem| sitem.key.as_str()).collect();
+			// if not force, we prompt
+			let perform_delete = opts.force || prompt_delete_confirmation("s3 objects", &keys_to_delete)?;
 			if perform_delete {
-				for key in keys_to_delete.iter() {
-					println!("DELETING {key}");
-					bucket.delete_object(key).await?;
-				}
+				bucket.delete_objects(&keys_to_delete).await?;
 			} else {
-				println!("CANCELLING delete");
+				print_msg("CANCELLING delete");
 			}
 		}
 		(url_1, url_2) => {
@@ -215,13 +295,101 @@ pub async fn exec_clean(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()>
 	Ok(())
 }
 
+/// Clean the local files that are not in s3 (`--reverse`)
+async fn exec_clean_reverse(bucket: &SBucket, local_path: &Path, s3_url: &S3Url, opts: &CleanOptions) -> Result<()> {
+	let files_to_delete = bucket.list_files_to_clean(local_path, s3_url.key(), opts).await?;
+
+	// if dry run, we just report
+	if opts.dry_run {
+		for file in files_to_delete.iter() {
+			print_dry_run_delete(&file.to_string_lossy(), file.metadata()?.len() as i64);
+		}
+		return Ok(());
+	}
+
+	if files_to_delete.is_empty() {
+		print_msg(f!("No local files to clean under {}", local_path.display()));
+		return Ok(());
+	}
+
+	let files: Vec<String> = files_to_delete.iter().map(|file| file.to_string_lossy().to_string()).collect();
+	let files: Vec<&str> = files.iter().map(|file| file.as_str()).collect();
+	// if not force, we prompt
+	let perform_delete = opts.force || prompt_delete_confirmation("local files", &files)?;
+	if perform_delete {
+		for file in files_to_delete.iter() {
+			let size = file.metadata()?.len();
+			fs::remove_file(file)?;
+			if is_text_output() {
+				println!("{:13} {}", "Deleted", file.display());
+			} else {
+				emit(Event::new("delete", file.to_string_lossy(), EventStatus::Ok).with_bytes(Some(size)));
+			}
+		}
+	} else {
+		print_msg("CANCELLING delete");
+	}
+
+	Ok(())
+}
+
 pub async fn exec_rb(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 	let s3_url = get_s3_url_1(argm)?;
 	let bucket_name = s3_url.bucket();
+	let dry_run = argm.get_flag(ARG_DRY_RUN);
+
+	// -- Force, empty the bucket first (all object versions, delete markers, and in progress multipart uploads)
+	if argm.get_flag(ARG_FORCE) {
+		let bucket = get_sbucket(reg_pro.clone(), bucket_name).await?;
+		let content = bucket.list_bucket_content().await?;
+		let counts = format!(
+			"{} object versions, {} delete markers, and {} in progress multipart uploads",
+			content.versions.len(),
+			content.delete_markers.len(),
+			content.uploads.len()
+		);
+
+		if dry_run {
+			if is_text_output() {
+				println!("Would delete bucket: {bucket_name}   (with {counts})");
+			} else {
+				emit(Event::new("delete_bucket", bucket_name, EventStatus::DryRun).with_reason(counts));
+			}
+			return Ok(());
+		}
+
+		if !content.is_empty() {
+			let response = prompt(&format!(
+				"Bucket '{bucket_name}' has {counts}.\nDelete them all and the bucket ('YES' to delete): "
+			))?;
+			if response != "YES" {
+				// Note: Not interactive (e.g., a script without the 'YES' on stdin), fail so that it is not taken as deleted
+				if !io::stdin().is_terminal() {
+					return Err(Error::RbNotConfirmed(bucket_name.to_string()));
+				}
+				print_msg("CANCELLING delete");
+				return Ok(());
+			}
+			bucket.empty_bucket(content).await?;
+		}
+	}
+
+	if dry_run {
+		if is_text_output() {
+			println!("Would delete bucket: {bucket_name}");
+		} else {
+			emit(Event::new("delete_bucket", bucket_name, EventStatus::DryRun));
+		}
+		return Ok(());
+	}
 
 	let client = new_s3_client(reg_pro, Some(bucket_name)).await?;
 	delete_bucket(&client, bucket_name).await?;
-	println!("Bucket Deleted: {bucket_name}");
+	if is_text_output() {
+		println!("Bucket Deleted: {bucket_name}");
+	} else {
+		emit(Event::new("delete_bucket", bucket_name, EventStatus::Ok));
+	}
 
 	Ok(())
 }
@@ -230,7 +398,7 @@ pub async fn exec_cp(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 	let url_1 = get_path_1(argm)?;
 	let url_2 = get_path_2(argm)?;
 
-	let opts = CpOptions::from_argm(argm);
+	let opts = CpOptions::from_argm(argm)?;
 
 	match (url_1, url_2) {
 		// DOWNLOAD
@@ -253,28 +421,261 @@ pub async fn exec_cp(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 			// perform the copy
 			dst_bucket.upload_path(&src_path, dst_s3.key(), opts).await?;
 		}
-		// UNSUPPORTED - for now, s3<->s3 or file<->file
+
+		// COPY (server side)
+		(SPath::S3(src_s3), SPath::S3(dst_s3)) => {
+			// Note: Each bucket gets its own client, as the credentials might be bucket specific (e.g., SS3_BUCKET_...)
+			let src_bucket = get_sbucket(reg_pro.clone(), src_s3.bucket()).await?;
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			// perform the copy
+			dst_bucket.copy_path(&src_bucket, src_s3.key(), dst_s3.key(), opts).await?;
+		}
+
+		// UPLOAD - stdin
+		(SPath::Std, SPath::S3(dst_s3)) => {
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			dst_bucket.upload_stdin(dst_s3.key(), opts).await?;
+		}
+
+		// DOWNLOAD - stdout (nothing else printed on stdout, not even the progress)
+		(SPath::S3(src_s3), SPath::Std) => {
+			set_stdout_streamed();
+			let src_bucket = get_sbucket(reg_pro, src_s3.bucket()).await?;
+			src_bucket.download_to_stdout(src_s3.key(), &opts).await?;
+			return Ok(());
+		}
+
+		// UNSUPPORTED - for now, file<->file
 		(url_1, url_2) => {
-			println!("NOT SUPPORTED - from {:?} to {:?} not supported", url_1, url_2);
+			print_msg(f!("NOT SUPPORTED - from {:?} to {:?} not supported", url_1, url_2));
+		}
+	}
+
+	print_msg("DONE");
+
+	Ok(())
+}
+
+pub async fn exec_mv(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
+	let url_1 = get_path_1(argm)?;
+	let url_2 = get_path_2(argm)?;
+
+	let opts = CpOptions::from_argm(argm)?;
+
+	match (url_1, url_2) {
+		// DOWNLOAD, then delete the objects
+		(SPath::S3(src_s3), SPath::File(dst_path)) => {
+			let src_bucket = get_sbucket(reg_pro, src_s3.bucket()).await?;
+			src_bucket.mv_download(src_s3.key(), &dst_path, opts).await?;
+		}
+
+		// UPLOAD, then delete the files
+		(SPath::File(src_path), SPath::S3(dst_s3)) => {
+			if !src_path.exists() {
+				return Err(Error::FilePathNotFound(src_path.display().to_string()));
+			}
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			dst_bucket.mv_upload(&src_path, dst_s3.key(), opts).await?;
+		}
+
+		// COPY (server side), then delete the source objects
+		(SPath::S3(src_s3), SPath::S3(dst_s3)) => {
+			let src_bucket = get_sbucket(reg_pro.clone(), src_s3.bucket()).await?;
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			dst_bucket.mv_copy(&src_bucket, src_s3.key(), dst_s3.key(), opts).await?;
+		}
+
+		(SPath::File(_), SPath::File(_)) => return Err(Error::CmdInvalid("The 'mv' command requires at least one S3 url.")),
+
+		(SPath::Std, _) | (_, SPath::Std) => {
+			return Err(Error::CmdInvalid("The 'mv' command does not support '-' (stdin/stdout)."));
 		}
 	}
 
-	println!("DONE");
+	print_msg("DONE");
+
+	Ok(())
+}
+
+pub async fn exec_sync(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
+	let url_1 = get_path_1(argm)?;
+	let url_2 = get_path_2(argm)?;
+
+	let opts = SyncOptions::from_argm(argm)?;
+
+	match (url_1, url_2) {
+		// LOCAL -> S3
+		(SPath::File(src_path), SPath::S3(dst_s3)) => {
+			if !src_path.is_dir() {
+				return Err(Error::FilePathNotFound(src_path.display().to_string()));
+			}
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			dst_bucket.sync_upload(&src_path, dst_s3.key(), opts).await?;
+		}
+
+		// S3 -> LOCAL
+		(SPath::S3(src_s3), SPath::File(dst_path)) => {
+			let src_bucket = get_sbucket(reg_pro, src_s3.bucket()).await?;
+			src_bucket.sync_download(src_s3.key(), &dst_path, opts).await?;
+		}
+
+		// S3 -> S3
+		(SPath::S3(src_s3), SPath::S3(dst_s3)) => {
+			let src_bucket = get_sbucket(reg_pro.clone(), src_s3.bucket()).await?;
+			let dst_bucket = get_sbucket(reg_pro, dst_s3.bucket()).await?;
+			dst_bucket.sync_copy(&src_bucket, src_s3.key(), dst_s3.key(), opts).await?;
+		}
+
+		(SPath::File(_), SPath::File(_)) => return Err(Error::CmdInvalid("The 'sync' command requires at least one S3 url.")),
+
+		(SPath::Std, _) | (_, SPath::Std) => {
+			return Err(Error::CmdInvalid("The 'sync' command does not support '-' (stdin/stdout)."));
+		}
+	}
+
+	print_msg("DONE");
 
 	Ok(())
 }
 
 pub async fn exec_rm(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
 	let s3_url = get_s3_url_1(argm)?;
+	let opts = RmOptions::from_argm(argm)?;
 
 	let bucket = get_sbucket(reg_pro, s3_url.bucket()).await?;
 
+	// -- Recursive, all the objects of the prefix (matching the includes/excludes), deleted by batches
+	if opts.recursive {
+		let sitems_to_delete = bucket.list_to_rm(s3_url.key(), &opts).await?;
+
+		// if dry run, we just report
+		if opts.dry_run {
+			for sitem in sitems_to_delete.iter() {
+				print_dry_run_delete(&bucket.s3_url(&sitem.key), sitem.size);
+			}
+			return Ok(());
+		}
+
+		if sitems_to_delete.is_empty() {
+			print_msg(f!("No s3 objects to delete under {s3_url}"));
+			return Ok(());
+		}
+
+		let keys_to_delete: Vec<&str> = sitems_to_delete.iter().map(|sitem| sitem.key.as_str()).collect();
+		// if not force, we prompt
+		if opts.force || prompt_delete_confirmation("s3 objects", &keys_to_delete)? {
+			bucket.delete_objects(&keys_to_delete).await?;
+		} else {
+			print_msg("CANCELLING delete");
+		}
+
+		return Ok(());
+	}
+
+	// -- Single object
+	if opts.includes.is_some() || opts.excludes.is_some() {
+		return Err(Error::CmdInvalid("The 'rm' '--include/--exclude' globs require '-r'."));
+	}
+
+	// if dry run, only head the object (for its size)
+	if opts.dry_run {
+		let sitem = bucket.head_sitem(s3_url.key()).await?;
+		print_dry_run_delete(&s3_url.to_string(), sitem.size);
+		return Ok(());
+	}
+
 	bucket.delete_object(s3_url.key()).await?;
-	println!("Object Deleted: {s3_url}");
+	if is_text_output() {
+		println!("Object Deleted: {s3_url}");
+	} else {
+		emit(Event::new("delete", s3_url.to_string(), EventStatus::Ok));
+	}
 
 	Ok(())
 }
 
+pub async fn exec_restore(reg_pro: RegionProfile, argm: &ArgMatches) -> Result<()> {
+	let s3_url = get_s3_url_1(argm)?;
+	let opts = RestoreOptions::from_argm(argm)?;
+
+	if !opts.recursive && (opts.includes.is_some() || opts.excludes.is_some()) {
+		return Err(Error::CmdInvalid("The 'restore' '--include/--exclude' globs require '-r'."));
+	}
+
+	let bucket = get_sbucket(reg_pro, s3_url.bucket()).await?;
+
+	let sitems = bucket.list_to_restore(s3_url.key(), &opts).await?;
+	if sitems.is_empty() {
+		print_msg(f!("No s3 objects to restore under {s3_url}"));
+		return Ok(());
+	}
+
+	let restoring_keys = bucket.restore_objects(&sitems, &opts).await?;
+
+	if opts.wait && !opts.dry_run && !restoring_keys.is_empty() {
+		bucket.wait_restored(restoring_keys).await?;
+	}
+
+	Ok(())
+}
+
+// region:    --- Print Utils
+
+/// Print the keys (or files) to be deleted, and prompt for the confirmation (true if 'YES')
+/// - `what` - e.g., "s3 objects", "local files"
+fn prompt_delete_confirmation(what: &str, keys_to_delete: &[&str]) -> Result<bool> {
+	print_msg(f!("Will delete {} {what}. List of all to be deleted:", keys_to_delete.len()));
+	for key in keys_to_delete.iter() {
+		print_msg(f!("    {key}"));
+	}
+	let response = prompt(&format!("\n{} {what} found to delete ('YES' to delete): ", keys_to_delete.len()))?;
+
+	Ok(response == "YES")
+}
+
+/// Print the long listing line of an object (last modified, size, storage class, etag, owner, key)
+fn print_long_sitem(sitem: &SItem, bytes: bool) {
+	let last_modified = sitem
+		.last_modified
+		.and_then(|dt| DateTime::from_secs(dt.secs()).fmt(DateTimeFormat::DateTime).ok())
+		.map(|dt| dt.replace('T', " ").trim_end_matches('Z').to_string())
+		.unwrap_or_else(|| s!("-"));
+	let restore = sitem.restore.as_ref().map(|status| format!("   ({status})")).unwrap_or_default();
+	println!(
+		"{last_modified:19}  {:>10}  {:19}  {:36}  {:16}  {}{restore}",
+		fmt_size(sitem.size, bytes),
+		sitem.storage_class.as_deref().unwrap_or("-"),
+		sitem.etag.as_deref().unwrap_or("-"),
+		sitem.owner.as_deref().unwrap_or("-"),
+		sitem.key
+	);
+}
+
+/// The size in bytes (`--bytes`), or human readable (default, `--human`)
+fn fmt_size(size: i64, bytes: bool) -> String {
+	if bytes { size.to_string() } else { fit_4(size.max(0) as u64) }
+}
+
+/// - `target` - the s3 url (or local file path) that would be deleted
+fn print_dry_run_delete(target: &str, size: i64) {
+	if is_text_output() {
+		println!("{:13} {target}   (size: {})", "Would delete", fit_4(size.max(0) as u64));
+	} else {
+		emit(Event::new("delete", target, EventStatus::DryRun).with_bytes(Some(size.max(0) as u64)));
+	}
+}
+
+/// Print a human message (to stderr for the json/ndjson output, so that stdout stays parsable)
+fn print_msg(msg: impl std::fmt::Display) {
+	if is_text_output() {
+		println!("{msg}");
+	} else {
+		eprintln!("{msg}");
+	}
+}
+
+// endregion: --- Print Utils
+
 // region:    --- Arg Utils
 
 fn get_s3_url_1(argm: &ArgMatches) -> Result<S3Url> {
@@ -332,6 +733,7 @@ impl ListOptions {
 			includes,
 			excludes,
 			info,
+			fetch_owner: argm.get_flag(ARG_LONG.0),
 			..Default::default()
 		})
 	}
@@ -342,16 +744,75 @@ impl ListOptions {
 
 impl CleanOptions {
 	fn from_argm(argm: &ArgMatches) -> Result<CleanOptions> {
-		let force = argm.get_flag(ARG_FORCE);
-		Ok(CleanOptions { force })
+		Ok(CleanOptions {
+			force: argm.get_flag(ARG_FORCE),
+			dry_run: argm.get_flag(ARG_DRY_RUN),
+			excludes: build_glob_set(argm, ARG_EXCLUDE),
+			includes: build_glob_set(argm, ARG_INCLUDE),
+			reverse: argm.get_flag(ARG_REVERSE),
+		})
+	}
+}
+
+impl RmOptions {
+	fn from_argm(argm: &ArgMatches) -> Result<RmOptions> {
+		Ok(RmOptions {
+			recursive: argm.get_flag(ARG_RECURSIVE.0),
+			excludes: build_glob_set(argm, ARG_EXCLUDE),
+			includes: build_glob_set(argm, ARG_INCLUDE),
+			force: argm.get_flag(ARG_FORCE),
+			dry_run: argm.get_flag(ARG_DRY_RUN),
+		})
+	}
+}
+
+impl RestoreOptions {
+	fn from_argm(argm: &ArgMatches) -> Result<RestoreOptions> {
+		// Note: The tier is matched case insensitive (e.g., 'bulk')
+		let tier = match argm.get_one::<String>(ARG_TIER).map(|tier| tier.to_lowercase()).as_deref() {
+			Some("bulk") => Tier::Bulk,
+			Some("expedited") => Tier::Expedited,
+			_ => Tier::Standard,
+		};
+
+		Ok(RestoreOptions {
+			recursive: argm.get_flag(ARG_RECURSIVE.0),
+			excludes: build_glob_set(argm, ARG_EXCLUDE),
+			includes: build_glob_set(argm, ARG_INCLUDE),
+			days: argm.get_one::<i32>(ARG_DAYS).copied().unwrap_or(DEFAULT_RESTORE_DAYS),
+			tier,
+			wait: argm.get_flag(ARG_WAIT),
+			dry_run: argm.get_flag(ARG_DRY_RUN),
+		})
 	}
 }
 
 // endregion: --- CleanOptions
 
+// region:    --- SyncOptions Builder
+
+impl SyncOptions {
+	fn from_argm(argm: &ArgMatches) -> Result<SyncOptions> {
+		Ok(SyncOptions {
+			excludes: build_glob_set(argm, "exclude"),
+			includes: build_glob_set(argm, "include"),
+			delete: argm.get_flag(ARG_DELETE),
+			show_skip: argm.get_flag("show-skip"),
+			header_rules: get_header_rules(argm)?,
+			sse: get_object_sse(argm)?,
+			multipart: get_multipart_options(argm)?,
+			concurrency: argm.get_one::<usize>(ARG_CONCURRENCY).copied().unwrap_or(1),
+			dry_run: argm.get_flag(ARG_DRY_RUN),
+			progress: get_progress(argm),
+		})
+	}
+}
+
+// endregion: --- SyncOptions Builder
+
 // region:    --- CpOptions Builder
 impl CpOptions {
-	fn from_argm(argm: &ArgMatches) -> CpOptions {
+	fn from_argm(argm: &ArgMatches) -> Result<CpOptions> {
 		// extract recursive flag
 		let recursive = argm.get_flag(ARG_RECURSIVE.0);
 		let show_skip = argm.get_flag("show-skip");
@@ -366,28 +827,163 @@ impl CpOptions {
 			Some("skip") => OverMode::Skip,
 			Some("etag") => OverMode::Etag,
 			Some("fail") => OverMode::Fail,
-			Some(other) => panic!("Invalid over mode {}. Must be 'write', 'skip', 'etag', 'fail'", other),
-			None => OverMode::default(),
+			Some("size") => OverMode::Size,
+			Some("newer") => OverMode::Newer,
+			Some("size-mtime") => OverMode::SizeMtime,
+			// Note: The other values are rejected by clap (see `arg_over`)
+			_ => OverMode::default(),
 		};
 
-		let noext_ct = argm.get_one::<String>(ARG_NOEXT_CT).map(|v| match v.as_str() {
-			"html" => s!(CT_HTML),
-			"text" => s!(CT_TEXT),
-			_ => s!(v),
-		});
+		// Note: cp only, as the compress mode (mv verifies the uploaded objects against the source files)
+		let encrypt_key = argm
+			.try_get_one::<String>(ARG_ENCRYPT_KEY_FILE)
+			.ok()
+			.flatten()
+			.map(|v| EncryptKey::from_file(Path::new(v)))
+			.transpose()?;
+		// Note: The encryption is randomized, so the encrypted objects never match the files on etag or size
+		if encrypt_key.is_some() && over.compares_content() {
+			return Err(Error::CmdInvalid(
+				"'--encrypt-key-file' cannot be used with '--over etag|size|size-mtime' (the encrypted objects do not compare with the files).",
+			));
+		}
+
+		// extract the multipart options and concurrency
+		let multipart = get_multipart_options(argm)?;
+		let concurrency = argm.get_one::<usize>(ARG_CONCURRENCY).copied().unwrap_or(1);
 
 		// build the options
-		CpOptions {
+		Ok(CpOptions {
 			recursive,
 			excludes,
 			includes,
 			over,
 			show_skip,
-			noext_ct,
-		}
+			content_type: argm.get_one::<String>(ARG_CONTENT_TYPE).cloned(),
+			headers: get_object_headers(argm)?,
+			header_rules: get_header_rules(argm)?,
+			// Note: cp only, as the encrypt key (mv verifies the uploaded objects against the source files)
+			compress: argm
+				.try_get_one::<String>(ARG_COMPRESS)
+				.ok()
+				.flatten()
+				.and_then(|v| v.parse::<CompressMode>().ok()),
+			encrypt_key,
+			sse: get_object_sse(argm)?,
+			multipart,
+			concurrency,
+			dry_run: argm.get_flag(ARG_DRY_RUN),
+			progress: get_progress(argm),
+		})
 	}
 }
 
+/// No progress on dry run (nothing is transferred)
+fn get_progress(argm: &ArgMatches) -> Progress {
+	// Note: No progress for the json/ndjson output (stdout is for the records)
+	if argm.get_flag(ARG_DRY_RUN) || !is_text_output() {
+		Progress::default()
+	} else {
+		Progress::new()
+	}
+}
+
+fn get_output_format(argm: &ArgMatches) -> Result<OutputFormat> {
+	let format = argm.get_one::<String>(ARG_OUTPUT).map(|v| v.as_str()).unwrap_or("text");
+	format
+		.parse::<OutputFormat>()
+		.map_err(|_| Error::CmdInvalid("The '--output' must be 'text', 'json', or 'ndjson'."))
+}
+
+/// The `--headers-file` rules, with the `--noext-ct` one
+fn get_header_rules(argm: &ArgMatches) -> Result<HeaderRules> {
+	let rules = match argm.get_one::<String>(ARG_HEADERS_FILE) {
+		Some(path) => HeaderRules::from_file(Path::new(path))?,
+		None => HeaderRules::default(),
+	};
+
+	Ok(match get_noext_ct(argm) {
+		Some(noext_ct) => rules.with_noext_ct(noext_ct),
+		None => rules,
+	})
+}
+
+fn get_noext_ct(argm: &ArgMatches) -> Option<String> {
+	argm.get_one::<String>(ARG_NOEXT_CT).map(|v| match v.as_str() {
+		"html" => s!(CT_HTML),
+		"text" => s!(CT_TEXT),
+		_ => s!(v),
+	})
+}
+
+fn get_object_headers(argm: &ArgMatches) -> Result<ObjectHeaders> {
+	let get_string = |name: &str| argm.get_one::<String>(name).cloned();
+
+	// RFC 3339 (e.g., '2030-01-31T10:00:00Z') or HTTP date (e.g., 'Thu, 31 Jan 2030 10:00:00 GMT')
+	let expires = argm
+		.get_one::<String>(ARG_EXPIRES)
+		.map(|v| {
+			DateTime::from_str(v, DateTimeFormat::DateTime)
+				.or_else(|_| DateTime::from_str(v, DateTimeFormat::HttpDate))
+				.map_err(|_| Error::InvalidExpires(v.to_string()))
+		})
+		.transpose()?;
+
+	let metadata = argm
+		.get_many::<String>(ARG_METADATA)
+		.map(|vals| {
+			vals
+				.map(|v| match v.split_once('=') {
+					Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+					_ => Err(Error::InvalidMetadata(v.to_string())),
+				})
+				.collect::<Result<HashMap<_, _>>>()
+		})
+		.transpose()?;
+
+	Ok(ObjectHeaders {
+		cache_control: get_string(ARG_CACHE_CONTROL),
+		content_encoding: get_string(ARG_CONTENT_ENCODING),
+		content_disposition: get_string(ARG_CONTENT_DISPOSITION),
+		content_language: get_string(ARG_CONTENT_LANGUAGE),
+		expires,
+		metadata,
+		storage_class: argm
+			.get_one::<String>(ARG_STORAGE_CLASS)
+			.map(|v| parse_storage_class(v))
+			.transpose()?,
+	})
+}
+
+fn get_object_sse(argm: &ArgMatches) -> Result<ObjectSse> {
+	let customer_key = argm
+		.get_one::<String>(ARG_SSE_C_KEY)
+		.map(|v| SseCustomerKey::from_arg(v))
+		.transpose()?;
+
+	ObjectSse::new(
+		argm.get_one::<String>(ARG_SSE).map(|v| v.as_str()),
+		argm.get_one::<String>(ARG_SSE_KMS_KEY_ID).cloned(),
+		customer_key,
+	)
+}
+
+fn get_multipart_options(argm: &ArgMatches) -> Result<MultipartOptions> {
+	let multipart_default = MultipartOptions::default();
+	MultipartOptions::new(
+		get_size_arg(argm, ARG_MULTIPART_THRESHOLD)?.unwrap_or(multipart_default.threshold),
+		get_size_arg(argm, ARG_PART_SIZE)?.unwrap_or(multipart_default.part_size),
+		argm
+			.get_one::<usize>(ARG_PART_CONCURRENCY)
+			.copied()
+			.unwrap_or(multipart_default.concurrency),
+	)
+}
+
+fn get_size_arg(argm: &ArgMatches, name: &str) -> Result<Option<u64>> {
+	argm.get_one::<String>(name).map(|v| parse_size(v)).transpose()
+}
+
 fn build_glob_set(argm: &ArgMatches, name: &str) -> Option<GlobSet> {
 	let globs = argm.get_many::<String>(name).map(|vals| vals.collect::<Vec<_>>());
 	globs.map(|globs| {
@@ -4,3 +4,13 @@ pub const DEFAULT_UPLOAD_IGNORE_FILES: &[&str; 1] = &[".DS_Store"];
 pub const DEFAULT_UPLOAD_IGNORE_GLOBS: &[&str; 1] = &["**/.DS_Store"];
 pub const CT_HTML: &str = "text/html; charset=UTF-8";
 pub const CT_TEXT: &str = "text/plain; charset=UTF-8";
+
+// -- Multipart (S3 limits and ss3 defaults)
+pub const MIB: u64 = 1024 * 1024;
+pub const S3_MIN_PART_SIZE: u64 = 5 * MIB;
+pub const S3_MAX_PART_SIZE: u64 = 5 * 1024 * MIB;
+pub const S3_MAX_PARTS: u64 = 10_000;
+pub const S3_MAX_COPY_OBJECT_SIZE: u64 = 5 * 1024 * MIB;
+pub const DEFAULT_MULTIPART_THRESHOLD: u64 = 8 * MIB;
+pub const DEFAULT_MULTIPART_PART_SIZE: u64 = 8 * MIB;
+pub const DEFAULT_PART_CONCURRENCY: usize = 4;
@@ -1,13 +1,23 @@
 use crate::utils;
 use aws_sdk_s3::error::SdkError;
+use aws_sdk_s3::operation::abort_multipart_upload::AbortMultipartUploadError;
+use aws_sdk_s3::operation::complete_multipart_upload::CompleteMultipartUploadError;
+use aws_sdk_s3::operation::copy_object::CopyObjectError;
 use aws_sdk_s3::operation::create_bucket::CreateBucketError;
+use aws_sdk_s3::operation::create_multipart_upload::CreateMultipartUploadError;
 use aws_sdk_s3::operation::delete_bucket::DeleteBucketError;
 use aws_sdk_s3::operation::delete_object::DeleteObjectError;
+use aws_sdk_s3::operation::delete_objects::DeleteObjectsError;
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::operation::head_object::HeadObjectError;
 use aws_sdk_s3::operation::list_buckets::ListBucketsError;
+use aws_sdk_s3::operation::list_multipart_uploads::ListMultipartUploadsError;
+use aws_sdk_s3::operation::list_object_versions::ListObjectVersionsError;
 use aws_sdk_s3::operation::list_objects_v2::ListObjectsV2Error;
 use aws_sdk_s3::operation::put_object::PutObjectError;
+use aws_sdk_s3::operation::restore_object::RestoreObjectError;
+use aws_sdk_s3::operation::upload_part::UploadPartError;
+use aws_sdk_s3::operation::upload_part_copy::UploadPartCopyError;
 use derive_more::{Display, From};
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -29,7 +39,71 @@ pub enum Error {
 	#[display("Invalid clean url. Must be valid `local file path` and then `s3 url/base path` (was '{url_1}' and then '{url_2}`) ")]
 	CleanInvalidArguments { url_1: String, url_2: String },
 
+	// -- Cp
+	#[display("{} of {total} transfers failed:\n  - {}", failures.len(), failures.join("\n  - "))]
+	CpTransfersFailed { total: usize, failures: Vec<String> },
+
+	// -- Mv
+	#[display("Move of '{src}' not verified (source kept). Destination '{dst}' does not match the source {cause}.")]
+	MvNotVerified { src: String, dst: String, cause: &'static str },
+
+	// -- Rm
+	#[display("{} of {total} deletes failed:\n  - {}", failures.len(), failures.join("\n  - "))]
+	DeleteObjectsFailed { total: usize, failures: Vec<String> },
+
+	// -- Rb
+	#[display("Delete of the not empty bucket '{_0}' not confirmed ('YES' on stdin). Nothing was deleted.")]
+	RbNotConfirmed(String),
+
+	// -- Restore
+	#[display("{} of {total} restores failed:\n  - {}", failures.len(), failures.join("\n  - "))]
+	RestoreFailed { total: usize, failures: Vec<String> },
+
+	// -- Download
+	#[display("Download size mismatch for key '{key}'. Expected {expected} bytes, but got {actual} bytes.")]
+	DownloadSizeMismatch { key: String, expected: u64, actual: u64 },
+
+	#[display("Download etag mismatch for key '{key}'. Expected '{expected}', but got '{actual}'.")]
+	DownloadEtagMismatch { key: String, expected: String, actual: String },
+
+	#[display("Object '{key}' is archived (storage class {storage_class}), it must be restored before being downloaded.")]
+	ObjectArchived { key: String, storage_class: String },
+
+	#[display("Object '{key}' is client side encrypted, it requires '--encrypt-key-file' to be downloaded.")]
+	ObjectEncryptKeyRequired { key: String },
+
+	#[display("Decryption of object '{key}' failed, {cause}. It was tampered with, or the key is not its key.")]
+	ObjectDecryptFailed { key: String, cause: &'static str },
+
+	// -- Multipart
+	#[display("Create multipart upload for key '{_0}' did not return an upload id")]
+	MultipartNoUploadId(String),
+
+	#[display("Invalid part size '{_0}'. Must be between 5MB and 5GB.")]
+	InvalidPartSize(u64),
+
 	// -- Uncategorized
+	#[display("Invalid size '{_0}'. Should be a number with an optional unit (e.g., '16MB', '512KB', '1GB')")]
+	InvalidSize(String),
+
+	#[display("Invalid expires '{_0}'. Should be a RFC 3339 date time (e.g., '2030-01-31T10:00:00Z') or a HTTP date")]
+	InvalidExpires(String),
+
+	#[display("Invalid storage class '{_0}'. Should be one of the S3 storage classes (e.g., 'STANDARD_IA', 'GLACIER_IR', 'GLACIER')")]
+	InvalidStorageClass(String),
+
+	#[display("Invalid headers file '{path}'. Cause: {cause}")]
+	HeadersFileInvalid { path: String, cause: String },
+
+	#[display("Invalid SSE-C key, {_0}. Should be a base64 encoded 256-bit key, or a key file (32 bytes, or base64)")]
+	InvalidSseCustomerKey(&'static str),
+
+	#[display("Invalid key file, {_0}. Should have a 256-bit key (32 bytes, or base64)")]
+	InvalidKeyFile(&'static str),
+
+	#[display("Invalid metadata '{_0}'. Should be 'key=value' (e.g., --metadata 'release=1.2.0')")]
+	InvalidMetadata(String),
+
 	#[display("Not a valid s3 url '{_0}'. Should be format 's3://bucket_name[/path/to/object]'")]
 	NotValidS3Url(String),
 
@@ -108,6 +182,9 @@ pub enum Error {
 	#[from]
 	SimpleFs(simple_fs::Error),
 
+	#[from]
+	AwsBuild(aws_sdk_s3::error::BuildError),
+
 	// aws_sdk_s3::primitives::ByteStreamError
 	#[from]
 	ByteStream(aws_sdk_s3::primitives::ByteStreamError),
@@ -117,6 +194,10 @@ pub enum Error {
 
 	#[from]
 	IO(std::io::Error),
+
+	// tokio::task::spawn_blocking (e.g., compression, encryption)
+	#[from]
+	Join(tokio::task::JoinError),
 }
 
 // region:    --- Custom
@@ -161,9 +242,19 @@ impl_from_sdk_error!(
 	DeleteBucketError,
 	GetObjectError,
 	DeleteObjectError,
+	DeleteObjectsError,
 	PutObjectError,
 	HeadObjectError,
-	ListObjectsV2Error
+	ListObjectsV2Error,
+	ListObjectVersionsError,
+	ListMultipartUploadsError,
+	CreateMultipartUploadError,
+	UploadPartError,
+	CompleteMultipartUploadError,
+	AbortMultipartUploadError,
+	CopyObjectError,
+	UploadPartCopyError,
+	RestoreObjectError
 );
 
 // For better CLI error reporting.
@@ -4,6 +4,7 @@
 mod cmd;
 mod consts;
 mod error;
+mod output;
 mod prompt;
 mod s3w;
 mod spath;
@@ -23,7 +24,7 @@ use std::process::ExitCode;
 
 // endregion: --- Modules
 
-#[tokio::main(flavor = "current_thread")]
+#[tokio::main]
 async fn main() -> ExitCode {
 	match cmd_run().await {
 		Ok(_) => ExitCode::SUCCESS,
@@ -0,0 +1,229 @@
+//! The output format of the commands (`--output text|json|ndjson`).
+//! - text: The human lines (printed by each command).
+//! - ndjson: One JSON record per line, printed as they happen.
+//! - json: The same records, printed as one JSON array at the end of the command.
+//!
+//! Note: The format is set once at the start of the command (global), so that the s3w layer
+//!       can emit its records (e.g., cp items, deletes) without having the format threaded through.
+
+use crate::s3w::{SItem, SItemType};
+use aws_sdk_s3::primitives::DateTimeFormat;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+// region:    --- OutputFormat
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::AsRefStr, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum OutputFormat {
+	#[default]
+	Text,
+	Json,
+	Ndjson,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// The records of the json output, printed at the end by `flush_output`
+static JSON_RECORDS: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+/// Set when stdout is the content of a downloaded object (`cp s3://... -`), so no record is written after it
+static STDOUT_STREAMED: AtomicBool = AtomicBool::new(false);
+
+/// Set the output format (only the first call is taken into account)
+pub fn init_output_format(format: OutputFormat) {
+	let _ = OUTPUT_FORMAT.set(format);
+}
+
+pub fn output_format() -> OutputFormat {
+	OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+pub fn is_text_output() -> bool {
+	output_format() == OutputFormat::Text
+}
+
+/// Emit a record (nothing for the text output, for which the commands print their own lines).
+pub fn emit(record: impl Into<Record>) {
+	let record = record.into();
+	match output_format() {
+		OutputFormat::Text => (),
+		OutputFormat::Ndjson => {
+			if let Ok(line) = serde_json::to_string(&record) {
+				write_line(&line);
+			}
+		}
+		OutputFormat::Json => {
+			if let Ok(mut records) = JSON_RECORDS.lock() {
+				records.push(record);
+			}
+		}
+	}
+}
+
+/// Print the records as one JSON array (json output only). To be called once, at the end of the command.
+pub fn flush_output() {
+	if output_format() != OutputFormat::Json {
+		return;
+	}
+	if let Ok(mut records) = JSON_RECORDS.lock()
+		&& let Ok(json) = serde_json::to_string_pretty(&std::mem::take(&mut *records))
+	{
+		write_line(&json);
+	}
+}
+
+/// Mark stdout as the content of a downloaded object (the json/ndjson records are not written, not to corrupt it)
+pub fn set_stdout_streamed() {
+	STDOUT_STREAMED.store(true, Ordering::Relaxed);
+}
+
+/// Write a line to stdout (nothing when stdout is a streamed object).
+/// Note: The write errors are ignored, so that a closed pipe (e.g., `| head`) does not panic like `println!`.
+fn write_line(line: &str) {
+	if STDOUT_STREAMED.load(Ordering::Relaxed) {
+		return;
+	}
+	let _ = writeln!(io::stdout().lock(), "{line}");
+}
+
+// endregion: --- OutputFormat
+
+// region:    --- Record
+
+/// A record of the json/ndjson output (with its "type" property, e.g., "object", "event")
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+	Bucket { name: String },
+	Prefix { key: String },
+	Object(ObjectRecord),
+	Info(InfoRecord),
+	Event(Event),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObjectRecord {
+	pub key: String,
+	pub size: i64,
+	pub etag: Option<String>,
+	/// RFC 3339 (e.g., "2024-01-31T10:00:00Z")
+	pub last_modified: Option<String>,
+	pub storage_class: Option<String>,
+	pub owner: Option<String>,
+	/// The restore status of the archived objects (with `ls -l`), e.g., "restoring", "restored until 2024-01-31T10:00:00Z"
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub restore: Option<String>,
+}
+
+impl From<&SItem> for Record {
+	fn from(sitem: &SItem) -> Self {
+		match sitem.typ {
+			SItemType::Prefix => Record::Prefix { key: sitem.key.clone() },
+			SItemType::Object => Record::Object(ObjectRecord {
+				key: sitem.key.clone(),
+				size: sitem.size,
+				etag: sitem.etag.clone(),
+				last_modified: sitem.last_modified.and_then(|dt| dt.fmt(DateTimeFormat::DateTime).ok()),
+				storage_class: sitem.storage_class.clone(),
+				owner: sitem.owner.clone(),
+				restore: sitem.restore.as_ref().map(|status| status.to_string()),
+			}),
+		}
+	}
+}
+
+/// The summary of a listing (`ls --info`)
+#[derive(Debug, Serialize)]
+pub struct InfoRecord {
+	pub total_count: i64,
+	pub total_size: i64,
+	pub extensions: Vec<ExtensionInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExtensionInfo {
+	pub ext: String,
+	pub size: i64,
+	pub count: i64,
+}
+
+impl From<InfoRecord> for Record {
+	fn from(info: InfoRecord) -> Self {
+		Record::Info(info)
+	}
+}
+
+// endregion: --- Record
+
+// region:    --- Event
+
+/// What was done (or not) for a file/object/bucket by a command (e.g., cp, rm, clean).
+#[derive(Debug, Serialize)]
+pub struct Event {
+	/// e.g., "upload", "download", "copy", "relay", "delete", "abort_upload", "create_bucket", "delete_bucket"
+	pub action: &'static str,
+	pub src: Option<String>,
+	pub dst: String,
+	pub bytes: Option<u64>,
+	pub status: EventStatus,
+	/// e.g., the skip reason ("Etag", "Exists")
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reason: Option<String>,
+	pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+	Ok,
+	Skipped,
+	Excluded,
+	DryRun,
+	Failed,
+}
+
+/// Builders
+impl Event {
+	pub fn new(action: &'static str, dst: impl Into<String>, status: EventStatus) -> Self {
+		Event {
+			action,
+			src: None,
+			dst: dst.into(),
+			bytes: None,
+			status,
+			reason: None,
+			error: None,
+		}
+	}
+
+	pub fn with_src(mut self, src: impl Into<String>) -> Self {
+		self.src = Some(src.into());
+		self
+	}
+
+	pub fn with_bytes(mut self, bytes: Option<u64>) -> Self {
+		self.bytes = bytes;
+		self
+	}
+
+	pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+		self.reason = Some(reason.into());
+		self
+	}
+
+	pub fn with_error(mut self, error: impl std::fmt::Display) -> Self {
+		self.error = Some(error.to_string());
+		self
+	}
+}
+
+impl From<Event> for Record {
+	fn from(event: Event) -> Self {
+		Record::Event(event)
+	}
+}
+
+// endregion: --- Event
@@ -1,10 +1,16 @@
 use crate::Result;
+use crate::output::is_text_output;
 use std::io::{self, Write};
 
+/// Note: For the json/ndjson output, the prompt is on stderr (stdout is for the records).
 pub fn prompt(msg: &str) -> Result<String> {
-	print!("{}", msg);
-	// flush
-	io::stdout().flush().expect("Cannot flush stdout ???");
+	if is_text_output() {
+		print!("{}", msg);
+		// flush
+		io::stdout().flush().expect("Cannot flush stdout ???");
+	} else {
+		eprint!("{}", msg);
+	}
 
 	let mut buff: String = String::new();
 	io::stdin().read_line(&mut buff).expect("Cannot read stdin ???");
@@ -1,41 +1,89 @@
-use crate::s3w::{ListOptions, SBucket, compute_dst_key};
+use crate::s3w::support::folder_prefix;
+use crate::s3w::{ListOptions, SBucket, SItem, compute_dst_key, validate_key};
 use crate::{DEFAULT_UPLOAD_IGNORE_GLOBS, Result};
+use globset::GlobSet;
 use simple_fs::list_files;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Default, Clone)]
 pub struct CleanOptions {
 	pub force: bool,
+	/// Only report the objects (or files) that would be deleted
+	pub dry_run: bool,
+	/// Matched against the s3 keys (for the local files, the key they would be uploaded to)
+	pub excludes: Option<GlobSet>,
+	pub includes: Option<GlobSet>,
+	/// Clean the local files that are not in s3 (rather than the s3 objects that are not local files)
+	pub reverse: bool,
 }
 
 impl SBucket {
-	/// List the files to clean given the local path and base_s3_path
-	/// IMPORTANT - For now, assume one self.list with no pagination
-	///      TODO - Fix the
-	pub async fn list_to_clean(&self, local_path: impl AsRef<Path>, base_s3_path: &str) -> Result<Vec<String>> {
+	/// List the s3 objects to clean given the local path and base_s3_path
+	/// (the objects of all the pages, matching the includes/excludes, without a local file).
+	///
+	/// Note: The base_s3_path is taken as a "folder" (trailing '/' added), so that `logs` does not also clean `logs-old/...` or `logs.txt`.
+	pub async fn list_to_clean(&self, local_path: impl AsRef<Path>, base_s3_path: &str, opts: &CleanOptions) -> Result<Vec<SItem>> {
 		let local_path = local_path.as_ref();
+		let base_s3_path = &folder_prefix(base_s3_path);
 
-		// -- get the sitems from the s3 (assume it will be ok to be within one page )
-		let sitems = self.list(base_s3_path, &ListOptions::new(true)).await?.objects;
+		// -- get the sitems from the s3 (all pages)
+		let list_options = ListOptions {
+			recursive: true,
+			includes: opts.includes.clone(),
+			excludes: opts.excludes.clone(),
+			..Default::default()
+		};
+		let sitems = self.list_all(base_s3_path, list_options).await?;
 
-		// -- List files and target s3 keys Set
-		let fs_options = simple_fs::ListOptions::new(Some(DEFAULT_UPLOAD_IGNORE_GLOBS));
-		let files = list_files(local_path, Some(&["**/*"]), Some(fs_options))?;
-		// the target s3 keys from the files
-		let target_key_set: HashSet<String> = files
-			.iter()
-			.map(|f| compute_dst_key(Some(local_path), f.std_path(), base_s3_path, false))
-			.collect::<Result<HashSet<_>>>()?;
+		// -- the target s3 keys Set from the local files
+		let target_key_set: HashSet<String> = list_keyed_files(local_path, base_s3_path)?
+			.into_iter()
+			.map(|(key, _)| key)
+			.collect();
 
 		// -- Build the result
-		let mut s3_keys: Vec<String> = Vec::new();
 		// for each remote s3 item, if not in local target key set then we should remove.
-		for sitem in sitems {
-			if !target_key_set.contains(&sitem.key) {
-				s3_keys.push(sitem.key)
-			}
-		}
-		Ok(s3_keys)
+		let to_clean = sitems.into_iter().filter(|sitem| !target_key_set.contains(&sitem.key)).collect();
+
+		Ok(to_clean)
 	}
+
+	/// List the local files to clean for the `reverse` mode
+	/// (the files matching the includes/excludes, without an object at the key they would be uploaded to).
+	pub async fn list_files_to_clean(&self, local_path: impl AsRef<Path>, base_s3_path: &str, opts: &CleanOptions) -> Result<Vec<PathBuf>> {
+		let local_path = local_path.as_ref();
+		let base_s3_path = &folder_prefix(base_s3_path);
+
+		// -- the s3 keys Set (all pages)
+		let s3_key_set: HashSet<String> = self
+			.list_all(base_s3_path, ListOptions::new(true))
+			.await?
+			.into_iter()
+			.map(|sitem| sitem.key)
+			.collect();
+
+		// -- Build the result
+		let to_clean = list_keyed_files(local_path, base_s3_path)?
+			.into_iter()
+			.filter(|(key, _)| validate_key(key, &opts.includes, &opts.excludes) && !s3_key_set.contains(key))
+			.map(|(_, file)| file)
+			.collect();
+
+		Ok(to_clean)
+	}
+}
+
+/// List the local files (without the default ignored ones) with their target s3 keys.
+fn list_keyed_files(local_path: &Path, base_s3_path: &str) -> Result<Vec<(String, PathBuf)>> {
+	let fs_options = simple_fs::ListOptions::new(Some(DEFAULT_UPLOAD_IGNORE_GLOBS));
+	let files = list_files(local_path, Some(&["**/*"]), Some(fs_options))?;
+
+	files
+		.iter()
+		.map(|f| {
+			let key = compute_dst_key(Some(local_path), f.std_path(), base_s3_path, false)?;
+			Ok((key, f.std_path().to_path_buf()))
+		})
+		.collect()
 }
@@ -0,0 +1,78 @@
+//! The pre-compression of the uploads (`--compress gzip|br`).
+//! - Each file is compressed to a temp file, which is then uploaded (and compared for the over modes) in place of the file.
+//! - When the compression does not reduce the size, the file is uploaded as is (no `Content-Encoding`).
+
+use crate::Result;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Brotli buffer size, quality (max), and window size (the default one)
+const BR_BUFFER_SIZE: usize = 64 * 1024;
+const BR_QUALITY: u32 = 11;
+const BR_LGWIN: u32 = 22;
+
+#[derive(Debug, Clone, Copy, strum::AsRefStr, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum CompressMode {
+	Gzip,
+	Br,
+}
+
+impl CompressMode {
+	/// The `Content-Encoding` of the compressed objects
+	pub fn content_encoding(&self) -> &'static str {
+		match self {
+			CompressMode::Gzip => "gzip",
+			CompressMode::Br => "br",
+		}
+	}
+
+	/// Same as `compress_file`, but on the blocking threads (so that a big file does not stall the other transfers).
+	pub(super) async fn compress_file_blocking(self, src_file: &Path) -> Result<Option<NamedTempFile>> {
+		let src_file = src_file.to_path_buf();
+		tokio::task::spawn_blocking(move || self.compress_file(&src_file)).await?
+	}
+
+	/// Compress `src_file` into a temp file (deleted when dropped).
+	/// Returns None when the compressed content is not smaller than the file.
+	///
+	/// Note: The compression is deterministic (e.g., gzip mtime of 0), so that the same file gives the same etag.
+	pub(super) fn compress_file(&self, src_file: &Path) -> Result<Option<NamedTempFile>> {
+		let mut reader = BufReader::new(File::open(src_file)?);
+		let mut temp_file = NamedTempFile::new()?;
+
+		{
+			let writer = BufWriter::new(temp_file.as_file_mut());
+			match self {
+				CompressMode::Gzip => {
+					let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::best());
+					io::copy(&mut reader, &mut encoder)?;
+					encoder.finish()?.flush()?;
+				}
+				CompressMode::Br => {
+					let mut encoder = brotli::CompressorWriter::new(writer, BR_BUFFER_SIZE, BR_QUALITY, BR_LGWIN);
+					io::copy(&mut reader, &mut encoder)?;
+					// Note: `into_inner` finishes the brotli stream
+					encoder.into_inner().flush()?;
+				}
+			}
+		}
+
+		let compressed_size = temp_file.as_file().metadata()?.len();
+		if compressed_size < src_file.metadata()?.len() {
+			Ok(Some(temp_file))
+		} else {
+			Ok(None)
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-compress.rs"]
+mod tests;
+
+// endregion: --- Tests
@@ -0,0 +1,217 @@
+use super::{Inex, ListOptions, PathType, SBucket, SItem, compute_dst_key, compute_inex, path_type};
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::encrypt::with_src_envelope;
+use crate::s3w::get::get_object_error;
+use crate::s3w::support::{CpOptions, copy_source, validate_over_for_s3_copy};
+use crate::s3w::{ObjectHeaders, SItemsCache};
+use crate::{Result, S3_MAX_COPY_OBJECT_SIZE};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::MetadataDirective;
+use futures::StreamExt;
+use std::path::Path;
+
+/// "cp copy" (s3 to s3) Implementation
+impl SBucket {
+	/// Copy an object or the objects of a prefix from `src_bucket` into this bucket, server side (no data goes through ss3).
+	///
+	/// When the two buckets resolve to different credentials/endpoints, the objects are relayed (streamed) through ss3 instead.
+	///
+	/// - `src_key` - A file like key (with extension) for a single object, otherwise a prefix (recursive if `opts.recursive`)
+	/// - `prefix` - The destination prefix, or the destination key when copying a single object with the same extension (rename)
+	///
+	/// - Objects are copied up to `opts.concurrency` at a time, but reported in the listing order.
+	pub async fn copy_path(&self, src_bucket: &SBucket, src_key: &str, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		self.copy_path_report(src_bucket, src_key, prefix, opts).await?.into_result()
+	}
+
+	/// Same as `copy_path`, but the failures are kept in the report (e.g., for mv to still move the copied objects).
+	pub(super) async fn copy_path_report(&self, src_bucket: &SBucket, src_key: &str, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		let src_key_path = Path::new(src_key);
+		let mut report = CpReport::new(&opts.progress);
+
+		match path_type(src_key_path) {
+			// -- Single object
+			PathType::File => {
+				let key = compute_dst_key(None, src_key_path, prefix, true)?;
+				let res = match src_bucket.head_sitem_sse(src_key, &opts.sse).await {
+					Ok(sitem) => self.copy_object_from(src_bucket, &sitem, &key, &opts, None).await,
+					Err(err) => Err(err),
+				};
+				report.add(src_bucket.s3_url(src_key), self.s3_url(&key), res, opts.show_skip);
+			}
+
+			// -- Objects of a prefix
+			PathType::Dir => {
+				let sitems = src_bucket.list_all(src_key, ListOptions::new(opts.recursive)).await?;
+
+				let sitems_cache = if opts.over.compares_dst() {
+					Some(self.sitems_cache(Some(prefix)).await?)
+				} else {
+					None
+				};
+
+				let sitems_and_keys = sitems
+					.iter()
+					.map(|sitem| Ok((sitem, compute_dst_key(Some(src_key_path), Path::new(&sitem.key), prefix, false)?)))
+					.collect::<Result<Vec<_>>>()?;
+
+				// copy them (buffered to preserve the listing order)
+				let (opts_ref, sitems_cache) = (&opts, sitems_cache.as_ref());
+				let mut results = futures::stream::iter(sitems_and_keys)
+					.map(|(sitem, key)| async move {
+						let res = self.copy_object_from(src_bucket, sitem, &key, opts_ref, sitems_cache).await;
+						(sitem, key, res)
+					})
+					.buffered(opts.concurrency());
+
+				while let Some((sitem, key, res)) = results.next().await {
+					report.add(src_bucket.s3_url(&sitem.key), self.s3_url(&key), res, opts.show_skip);
+				}
+			}
+		}
+
+		Ok(report)
+	}
+
+	/// Lower level function that copy a single object to a fully resolved key.
+	/// Note: Objects above 5GB (max of `copy_object`) are copied by parts with `upload_part_copy`.
+	pub(super) async fn copy_object_from(
+		&self,
+		src_bucket: &SBucket,
+		src: &SItem,
+		key: &str,
+		opts: &CpOptions,
+		sitems_cache: Option<&SItemsCache>,
+	) -> Result<CpItem> {
+		let src_url = src_bucket.s3_url(&src.key);
+		let dst_url = self.s3_url(key);
+
+		let item = match compute_inex(&src.key, &opts.includes, &opts.excludes) {
+			Inex::Include => {
+				if validate_over_for_s3_copy(self, key, src, opts, sitems_cache).await? {
+					let size = src.size.max(0) as u64;
+					let same_cred = self.has_same_cred(src_bucket);
+
+					if opts.dry_run {
+						let verb = if same_cred { "copy" } else { "relay" };
+						return Ok(CpItem::new(CpAction::DryRun { verb, size }, src_url, dst_url));
+					}
+
+					// -- Relay when the source bucket is not reachable with the destination credential
+					if !same_cred {
+						self.relay_object_from(src_bucket, src, key, size, opts).await?;
+						return Ok(CpItem::new(CpAction::Relay, src_url, dst_url).with_size(size));
+					}
+
+					// -- Otherwise, server side copy
+					if size > S3_MAX_COPY_OBJECT_SIZE {
+						// Note: Need the source content type and headers, as not carried by the multipart copy
+						let src_head = src_bucket.head_object_sse(&src.key, &opts.sse).await?;
+						let headers = opts.headers.for_copy_of(ObjectHeaders::from_head(&src_head));
+						self
+							.copy_object_multipart(
+								&src_bucket.name,
+								&src.key,
+								key,
+								size,
+								src_head.content_type(),
+								&with_src_envelope(&headers, src_head.metadata()),
+								&opts.sse,
+								&opts.multipart,
+							)
+							.await?;
+					} else {
+						let builder = self
+							.client
+							.copy_object()
+							.copy_source(copy_source(&src_bucket.name, &src.key))
+							.bucket(&self.name)
+							.key(key);
+						// Note: The given headers replace all of the source ones, so the source content type
+						//       (and the client side encryption envelope) is set back
+						let builder = if opts.headers.replaces_metadata() {
+							let src_head = src_bucket.head_object_sse(&src.key, &opts.sse).await?;
+							let builder = builder
+								.metadata_directive(MetadataDirective::Replace)
+								.set_content_type(src_head.content_type().map(|ct| ct.to_string()));
+							with_src_envelope(&opts.headers, src_head.metadata()).apply_to_copy(builder)
+						} else {
+							opts.headers.apply_to_copy(builder)
+						};
+						opts.sse.apply_to_copy(builder).send().await?;
+					}
+
+					CpItem::new(CpAction::Copy, src_url, dst_url).with_size(size)
+				} else {
+					CpItem::new(CpAction::Skip { reason: opts.over.label() }, src_url, dst_url)
+				}
+			}
+			Inex::ExcludeInExclude => CpItem::new(CpAction::Exclude, src_url, dst_url),
+			// if there is an include and not in include, we silently skip it
+			Inex::ExcludeNotInInclude => CpItem::new(CpAction::Ignore, src_url, dst_url),
+		};
+
+		Ok(item)
+	}
+
+	/// Stream an object from a bucket with a different credential/endpoint through ss3 (without local file).
+	/// The source headers and metadata are kept, as a server side copy does (unless replaced by the given ones).
+	/// Note: Below the multipart threshold, the object is read in memory and put in one request.
+	async fn relay_object_from(&self, src_bucket: &SBucket, src: &SItem, key: &str, size: u64, opts: &CpOptions) -> Result<()> {
+		let multipart = &opts.multipart;
+		if multipart.is_multipart(size) {
+			let src_head = src_bucket.head_object_sse(&src.key, &opts.sse).await?;
+			let headers = opts.headers.for_copy_of(ObjectHeaders::from_head(&src_head));
+			self
+				.relay_object_multipart(
+					src_bucket,
+					&src.key,
+					src.etag.as_deref(),
+					key,
+					size,
+					src_head.content_type(),
+					&with_src_envelope(&headers, src_head.metadata()),
+					&opts.sse,
+					multipart,
+				)
+				.await
+		} else {
+			let builder = src_bucket
+				.client
+				.get_object()
+				.bucket(&src_bucket.name)
+				.key(&src.key)
+				.set_if_match(src.etag.as_ref().map(|etag| format!("\"{etag}\"")));
+			let resp = opts
+				.sse
+				.apply_to_get(builder)
+				.send()
+				.await
+				.map_err(|err| get_object_error(&src.key, err))?;
+			let content_type = resp.content_type().map(|ct| ct.to_string());
+			let headers = opts.headers.for_copy_of(ObjectHeaders::from_get(&resp));
+			let headers = with_src_envelope(&headers, resp.metadata());
+			let data = resp.body.collect().await?.into_bytes();
+
+			let builder = self
+				.client
+				.put_object()
+				.bucket(&self.name)
+				.key(key)
+				.set_content_type(content_type)
+				.body(ByteStream::from(data));
+			let builder = headers.apply_to_put(builder);
+			opts.sse.apply_to_put(builder).send().await?;
+
+			Ok(())
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-cp-copy.rs"]
+mod tests;
+
+// endregion: --- Tests
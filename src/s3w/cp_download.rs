@@ -1,18 +1,33 @@
 use super::{Inex, ListOptions, ListResult, PathType, SBucket, SItem, compute_dst_path, compute_inex, get_file_name, path_type};
 use crate::{Error, Result};
 use aws_sdk_s3::primitives::ByteStream;
+use futures::StreamExt;
 use std::collections::{HashSet, VecDeque};
 use std::fs::{File, create_dir_all};
 use std::io::{BufWriter, Write};
 use std::ops::Deref;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 // use tokio_stream::StreamExt;
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::encrypt::Decryption;
+use crate::s3w::get::get_object_error;
+use crate::s3w::sse::head_etag_is_md5;
 use crate::s3w::support::{CpOptions, validate_over_for_file_dest};
 
 /// "cp download" Implementation
 impl SBucket {
-	pub async fn download_path(&self, base_key: &str, dst_path: &Path, opts: CpOptions) -> Result<()> {
+	/// Download an object or the objects of a prefix (recursive if `opts.recursive`) to a local file or directory.
+	///
+	/// - Objects are downloaded up to `opts.concurrency` at a time, but reported in the listing order.
+	///   A failing object does not stop the others, and the failures are returned as a `CpTransfersFailed` error.
+	pub async fn download_path(&self, base_key: &str, dst_path: &Path, opts: CpOptions) -> Result<CpReport> {
+		self.download_path_report(base_key, dst_path, opts).await?.into_result()
+	}
+
+	/// Same as `download_path`, but the failures are kept in the report (e.g., for mv to still move the transferred objects).
+	pub(super) async fn download_path_report(&self, base_key: &str, dst_path: &Path, opts: CpOptions) -> Result<CpReport> {
 		let key_path = Path::new(base_key);
+		let mut report = CpReport::new(&opts.progress);
 		match (path_type(key_path), path_type(dst_path)) {
 			// S3 File to Path File or Dir
 			(PathType::File, dst_type) => {
@@ -23,14 +38,16 @@ impl SBucket {
 					PathType::Dir => dst_path.join(file_name),
 				};
 
-				// create parent
+				// create parent (not in dry run, as nothing is downloaded)
 				if let Some(dst_dir) = dst_file.parent()
+					&& !opts.dry_run
 					&& !dst_dir.exists()
 				{
 					create_dir_all(dst_dir)?;
 				}
 				// perform the copy
-				self.download_file(base_key, &dst_file, &opts).await?;
+				let res = self.download_file(base_key, &dst_file, &opts, None).await;
+				report.add(self.s3_url(base_key), dst_file.to_string_lossy(), res, opts.show_skip);
 			}
 			// S3 Dir Path dir
 			(PathType::Dir, PathType::Dir) => {
@@ -58,11 +75,14 @@ impl SBucket {
 							objects,
 							next_continuation_token,
 						} = self.list(&prefix.key, &list_opts).await?;
-						// download the objects of this prefix
+						// compute the destination files of this prefix objects (and create their parent dirs)
+						let mut items_and_files: Vec<(&SItem, PathBuf)> = Vec::new();
 						for item in objects.iter() {
 							let dst_file = compute_dst_path(base_key, &item.key, dst_path)?;
 
-							if let Some(dst_file_parent) = dst_file.parent() {
+							if let Some(dst_file_parent) = dst_file.parent()
+								&& !opts.dry_run
+							{
 								let parent_dir_string = dst_file_parent.to_string_lossy();
 								if !dir_exist_set.contains(parent_dir_string.deref()) || !dst_file_parent.exists() {
 									create_dir_all(dst_file_parent)?;
@@ -70,7 +90,20 @@ impl SBucket {
 								}
 							}
 
-							self.download_file(&item.key, &dst_file, &opts).await?;
+							items_and_files.push((item, dst_file));
+						}
+
+						// download the objects of this prefix (buffered to preserve the listing order)
+						let opts_ref = &opts;
+						let mut results = futures::stream::iter(items_and_files)
+							.map(|(item, dst_file)| async move {
+								let res = self.download_file(&item.key, &dst_file, opts_ref, Some(item)).await;
+								(item, dst_file, res)
+							})
+							.buffered(opts.concurrency());
+
+						while let Some((item, dst_file, res)) = results.next().await {
+							report.add(self.s3_url(&item.key), dst_file.to_string_lossy(), res, opts.show_skip);
 						}
 
 						// if the download is recursive add those prefixes to the prefix_queue
@@ -87,43 +120,92 @@ impl SBucket {
 			// S3 dir to file (NOT supported)
 			(PathType::Dir, PathType::File) => return Err(Error::NotSupported("S3 Dir to Path File")),
 		}
-		Ok(())
+
+		Ok(report)
 	}
 
-	async fn download_file(&self, key: &str, dst_file: &Path, opts: &CpOptions) -> Result<()> {
-		match compute_inex(key, &opts.includes, &opts.excludes) {
+	/// Download a single object to a fully resolved file path.
+	/// - `sitem` - The listing item of the object if available (otherwise, a head request is done to get the size and etag)
+	pub(super) async fn download_file(&self, key: &str, dst_file: &Path, opts: &CpOptions, sitem: Option<&SItem>) -> Result<CpItem> {
+		let src = self.s3_url(key);
+		let dst = dst_file.to_string_lossy();
+
+		let item = match compute_inex(key, &opts.includes, &opts.excludes) {
 			Inex::Include => {
-				if validate_over_for_file_dest(dst_file, opts)? {
-					println!(
-						"{:20} s3://{}/{:40} to {}",
-						"Downloading",
-						self.name,
-						key,
-						dst_file.to_string_lossy()
-					);
-					// BUILD - aws s3 get request
-					let builder = self.client.get_object().bucket(&self.name).key(key);
-
-					let resp = builder.send().await?;
-
-					// Streaming
-					let mut data: ByteStream = resp.body;
-					let file = File::create(dst_file)?;
-					let mut buf_writer = BufWriter::new(file);
-					while let Some(bytes) = data.try_next().await? {
-						buf_writer.write_all(&bytes)?;
+				// -- Get the object size and etag (from listing or head)
+				let head_sitem;
+				let sitem = match sitem {
+					Some(sitem) => sitem,
+					None => {
+						head_sitem = self.head_sitem_sse(key, &opts.sse).await?;
+						&head_sitem
+					}
+				};
+
+				if validate_over_for_file_dest(dst_file, sitem, opts)? {
+					let size = sitem.size.max(0) as u64;
+
+					if opts.dry_run {
+						return Ok(CpItem::new(CpAction::DryRun { verb: "download", size }, src, dst));
 					}
-					buf_writer.flush()?;
+
+					let progress = opts.progress.start_file(&src, size);
+
+					// -- Download by ranges when above the multipart threshold, otherwise single stream
+					// Note: The client side encrypted objects are downloaded to a temp file, and then decrypted to `dst_file`.
+					let decryption = if opts.multipart.is_multipart(size) {
+						// Note: The ranges do not tell if the object is client side (or SSE-KMS/SSE-C) encrypted, so the head does
+						let head = self.head_object_sse(key, &opts.sse).await?;
+						let decryption = Decryption::for_object(key, head.metadata(), opts.encrypt_key.as_ref(), dst_file)?;
+						let download_file = decryption.as_ref().map(|d| d.encrypted_file()).unwrap_or(dst_file);
+						self
+							.download_file_ranged(
+								key,
+								download_file,
+								size,
+								sitem.etag.as_deref(),
+								head_etag_is_md5(&head),
+								&opts.sse,
+								&opts.multipart,
+								&progress,
+							)
+							.await?;
+						decryption
+					} else {
+						// BUILD - aws s3 get request
+						let builder = self.client.get_object().bucket(&self.name).key(key);
+						let builder = opts.sse.apply_to_get(builder);
+
+						let resp = builder.send().await.map_err(|err| get_object_error(key, err))?;
+						let decryption = Decryption::for_object(key, resp.metadata(), opts.encrypt_key.as_ref(), dst_file)?;
+						let download_file = decryption.as_ref().map(|d| d.encrypted_file()).unwrap_or(dst_file);
+
+						// Streaming
+						let mut data: ByteStream = resp.body;
+						let file = File::create(download_file)?;
+						let mut buf_writer = BufWriter::new(file);
+						while let Some(bytes) = data.try_next().await? {
+							buf_writer.write_all(&bytes)?;
+							progress.inc(bytes.len() as u64);
+						}
+						buf_writer.flush()?;
+						decryption
+					};
+
+					if let Some(decryption) = decryption {
+						decryption.decrypt_to_blocking(key, dst_file).await?;
+					}
+
+					CpItem::new(CpAction::Download, src, dst).with_size(size)
 				} else {
-					println!("{:20} {}", "Skip (exists)", dst_file.display());
+					CpItem::new(CpAction::Skip { reason: opts.over.label() }, src, dst)
 				}
 			}
-			Inex::ExcludeInExclude => {
-				println!("{:20} {}", "Excludes", self.s3_url(key));
-			}
+			Inex::ExcludeInExclude => CpItem::new(CpAction::Exclude, src, dst),
 			// if there is an include and not in incluse, we silently skip it
-			Inex::ExcludeNotInInclude => (),
-		}
-		Ok(())
+			Inex::ExcludeNotInInclude => CpItem::new(CpAction::Ignore, src, dst),
+		};
+
+		Ok(item)
 	}
 }
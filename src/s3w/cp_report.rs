@@ -0,0 +1,187 @@
+use crate::output::{Event, EventStatus, emit, is_text_output};
+use crate::s3w::Progress;
+use crate::{Error, Result};
+use file_size::fit_4;
+
+// region:    --- CpItem
+
+#[derive(Debug)]
+pub enum CpAction {
+	Upload {
+		content_type: String,
+		/// e.g., "gzip" when compressed by ss3 (`--compress`)
+		content_encoding: Option<&'static str>,
+		/// Client side encrypted by ss3 (`--encrypt-key-file`)
+		encrypted: bool,
+	},
+	Download,
+	/// Server side copy (s3 to s3)
+	Copy,
+	/// Streamed through ss3 (s3 to s3 with different credentials/endpoints)
+	Relay,
+	/// Deleted (sync `--delete` destinations, and mv sources)
+	Delete,
+	/// What would have been done without `--dry-run` (e.g., verb "upload", "download", "delete")
+	DryRun {
+		verb: &'static str,
+		size: u64,
+	},
+	/// Skipped because of the over mode or default ignore (the reason is displayed, e.g., "Etag", "Exists")
+	Skip {
+		reason: &'static str,
+	},
+	/// Explicitly excluded by an exclude glob
+	Exclude,
+	/// Silently ignored (e.g., not matching the includes)
+	Ignore,
+}
+
+/// Result of the cp of a single file/object
+#[derive(Debug)]
+pub struct CpItem {
+	pub action: CpAction,
+	pub src: String,
+	pub dst: String,
+	/// The bytes transferred (when known)
+	pub size: Option<u64>,
+}
+
+impl CpItem {
+	pub fn new(action: CpAction, src: impl Into<String>, dst: impl Into<String>) -> Self {
+		CpItem {
+			action,
+			src: src.into(),
+			dst: dst.into(),
+			size: None,
+		}
+	}
+
+	pub fn with_size(mut self, size: u64) -> Self {
+		self.size = Some(size);
+		self
+	}
+
+	/// The event of the json/ndjson output (None when nothing would be printed in text)
+	fn to_event(&self, show_skip: bool) -> Option<Event> {
+		let CpItem { src, dst, size, .. } = self;
+		let transfer = |action| Event::new(action, dst, EventStatus::Ok).with_src(src).with_bytes(*size);
+		let event = match &self.action {
+			CpAction::Upload { .. } => transfer("upload"),
+			CpAction::Download => transfer("download"),
+			CpAction::Copy => transfer("copy"),
+			CpAction::Relay => transfer("relay"),
+			CpAction::Delete => Event::new("delete", dst, EventStatus::Ok).with_bytes(*size),
+			CpAction::DryRun { verb: "delete", size } => Event::new("delete", dst, EventStatus::DryRun).with_bytes(Some(*size)),
+			CpAction::DryRun { verb, size } => Event::new(verb, dst, EventStatus::DryRun).with_src(src).with_bytes(Some(*size)),
+			CpAction::Skip { reason } if show_skip => Event::new("transfer", dst, EventStatus::Skipped).with_src(src).with_reason(*reason),
+			CpAction::Exclude => Event::new("transfer", dst, EventStatus::Excluded).with_src(src),
+			CpAction::Skip { .. } | CpAction::Ignore => return None,
+		};
+		Some(event)
+	}
+
+	fn print(&self, show_skip: bool) {
+		let CpItem { src, dst, .. } = self;
+		match &self.action {
+			CpAction::Upload {
+				content_type,
+				content_encoding,
+				encrypted,
+			} => {
+				let content_encoding = content_encoding.map(|ce| format!(", content-encoding: {ce}")).unwrap_or_default();
+				let encrypted = if *encrypted { ", client side encrypted" } else { "" };
+				println!(
+					"{:13} {src:50} --> {dst}   (content-type: {content_type}{content_encoding}{encrypted})",
+					"Uploading"
+				)
+			}
+			CpAction::Download => println!("{:20} {src:40} to {dst}", "Downloading"),
+			CpAction::Copy => println!("{:13} {src:50} --> {dst}", "Copying"),
+			CpAction::Relay => println!("{:13} {src:50} --> {dst}", "Relaying"),
+			CpAction::Delete => println!("{:13} {dst}", "Deleting"),
+			// Note: A delete is only about the dst (same as the rm/clean dry run lines)
+			CpAction::DryRun { verb: "delete", size } => println!("{:13} {dst}   (size: {})", "Would delete", fit_4(*size)),
+			CpAction::DryRun { verb, size } => {
+				println!("{:13} {src:50} --> {dst}   (size: {})", format!("Would {verb}"), fit_4(*size))
+			}
+			CpAction::Skip { reason } => {
+				if show_skip {
+					println!("{:13} - {dst}", format!("Skip ({reason})"))
+				}
+			}
+			CpAction::Exclude => println!("{:20} {src}", "Excludes"),
+			CpAction::Ignore => (),
+		}
+	}
+}
+
+// endregion: --- CpItem
+
+// region:    --- CpReport
+
+#[derive(Debug)]
+pub struct CpFailure {
+	pub src: String,
+	pub dst: String,
+	pub error: Error,
+}
+
+/// Report of a cp operation. Items and failures are in the order of the listing (regardless of the concurrency).
+#[derive(Debug, Default)]
+pub struct CpReport {
+	pub items: Vec<CpItem>,
+	pub failures: Vec<CpFailure>,
+	/// The progress of the transfers (the lines are printed without the progress bars drawn over them)
+	progress: Progress,
+}
+
+impl CpReport {
+	pub fn new(progress: &Progress) -> Self {
+		CpReport {
+			progress: progress.clone(),
+			..Default::default()
+		}
+	}
+
+	/// Add the result of a single cp, and print it (failures are printed to stderr).
+	pub fn add(&mut self, src: impl Into<String>, dst: impl Into<String>, res: Result<CpItem>, show_skip: bool) {
+		match res {
+			Ok(item) => {
+				if is_text_output() {
+					self.progress.suspend(|| item.print(show_skip));
+				} else if let Some(event) = item.to_event(show_skip) {
+					emit(event);
+				}
+				self.items.push(item);
+			}
+			Err(error) => {
+				let (src, dst) = (src.into(), dst.into());
+				if is_text_output() {
+					self.progress.suspend(|| eprintln!("{:13} {src:50} --> {dst}", "FAILED"));
+				} else {
+					emit(Event::new("transfer", &dst, EventStatus::Failed).with_src(&src).with_error(&error));
+				}
+				self.failures.push(CpFailure { src, dst, error });
+			}
+		}
+	}
+
+	/// Returns the report if no failure, otherwise a `CpTransfersFailed` error summarizing the failures.
+	/// Note: This is the end of the transfers, so the progress is cleared.
+	pub fn into_result(self) -> Result<CpReport> {
+		self.progress.finish();
+		if self.failures.is_empty() {
+			Ok(self)
+		} else {
+			let total = self.items.len() + self.failures.len();
+			let failures = self
+				.failures
+				.into_iter()
+				.map(|f| format!("{} --> {}\n    Cause: {}", f.src, f.dst, f.error))
+				.collect();
+			Err(Error::CpTransfersFailed { total, failures })
+		}
+	}
+}
+
+// endregion: --- CpReport
@@ -0,0 +1,168 @@
+use super::SBucket;
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::encrypt::is_client_encrypted;
+use crate::s3w::get::get_object_error;
+use crate::s3w::support::{CpOptions, OverMode};
+use crate::{Error, Result};
+use aws_sdk_s3::primitives::ByteStream;
+use file_size::fit_4;
+use std::io::ErrorKind;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+/// The content type of the stdin uploads when no `--content-type`
+const STDIN_DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The src/dst display of stdin/stdout
+const STD_PATH: &str = "-";
+
+/// "cp stdin/stdout" Implementation
+impl SBucket {
+	/// Upload stdin to a fully resolved key.
+	///
+	/// - The content length is unknown, so stdin is read part by part (`opts.multipart.part_size`).
+	///   If it fits in one part, it is a single put, otherwise a multipart upload (uploading the parts as they are read).
+	/// - Only the write, skip, and fail over modes are supported (the others need the source content upfront).
+	pub async fn upload_stdin(&self, key: &str, opts: CpOptions) -> Result<CpReport> {
+		if key.is_empty() || key.ends_with('/') {
+			return Err(Error::NotSupported("stdin to S3 Dir (the full key is required)"));
+		}
+		if opts.compress.is_some() {
+			return Err(Error::NotSupported("'--compress' for stdin"));
+		}
+		if opts.encrypt_key.is_some() {
+			return Err(Error::NotSupported("'--encrypt-key-file' for stdin"));
+		}
+
+		let mut report = CpReport::new(&opts.progress);
+		let res = self.upload_reader(tokio::io::stdin(), key, &opts).await;
+		report.add(STD_PATH, self.s3_url(key), res, opts.show_skip);
+
+		report.into_result()
+	}
+
+	/// Download an object to stdout.
+	/// Note: Nothing else is printed on stdout (so that it can be piped), and a closed pipe (e.g., `| head`) is not an error.
+	pub async fn download_to_stdout(&self, key: &str, opts: &CpOptions) -> Result<()> {
+		if key.is_empty() || key.ends_with('/') {
+			return Err(Error::NotSupported("S3 Dir to stdout"));
+		}
+
+		if opts.dry_run {
+			let sitem = self.head_sitem_sse(key, &opts.sse).await?;
+			eprintln!(
+				"{:13} {:50} --> {STD_PATH}   (size: {})",
+				"Would download",
+				self.s3_url(key),
+				fit_4(sitem.size.max(0) as u64)
+			);
+			return Ok(());
+		}
+
+		let builder = self.client.get_object().bucket(&self.name).key(key);
+		let resp = opts
+			.sse
+			.apply_to_get(builder)
+			.send()
+			.await
+			.map_err(|err| get_object_error(key, err))?;
+
+		// Note: The decryption authenticates the whole object first, so not streamed to stdout
+		if is_client_encrypted(resp.metadata()) {
+			return Err(Error::NotSupported("client side encrypted object to stdout"));
+		}
+
+		let mut data: ByteStream = resp.body;
+		let mut stdout = tokio::io::stdout();
+		let res = async {
+			while let Some(bytes) = data.try_next().await? {
+				stdout.write_all(&bytes).await?;
+			}
+			stdout.flush().await?;
+			Ok::<(), Error>(())
+		}
+		.await;
+
+		match res {
+			Err(Error::IO(err)) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			other => other,
+		}
+	}
+
+	async fn upload_reader(&self, mut reader: impl AsyncRead + Unpin, key: &str, opts: &CpOptions) -> Result<CpItem> {
+		let src = STD_PATH;
+		let dst = self.s3_url(key);
+
+		let write = match opts.over {
+			OverMode::Write => true,
+			OverMode::Skip => !self.exists(key, &opts.sse).await,
+			OverMode::Fail => {
+				if self.exists(key, &opts.sse).await {
+					return Err(Error::ObjectExistsOverFailMode(dst));
+				}
+				true
+			}
+			OverMode::Etag | OverMode::Size | OverMode::Newer | OverMode::SizeMtime => {
+				return Err(Error::NotSupported("'--over etag/size/newer/size-mtime' for stdin"));
+			}
+		};
+		if !write {
+			return Ok(CpItem::new(CpAction::Skip { reason: opts.over.label() }, src, dst));
+		}
+
+		// Note: For the dry run, stdin is still read (not uploaded) to report its size.
+		if opts.dry_run {
+			let size = tokio::io::copy(&mut reader, &mut tokio::io::sink()).await?;
+			return Ok(CpItem::new(CpAction::DryRun { verb: "upload", size }, src, dst));
+		}
+
+		let (content_type, headers) = opts.header_rules.resolve(key, opts.content_type.as_deref(), &opts.headers);
+		let content_type = content_type.unwrap_or_else(|| STDIN_DEFAULT_CONTENT_TYPE.to_string());
+		let progress = opts.progress.start_file(src, 0);
+
+		// -- Single put if it fits in one part, otherwise multipart from this first part
+		let part_size = opts.multipart.part_size;
+		let first_part = read_part(&mut reader, part_size).await?;
+		if (first_part.len() as u64) < part_size {
+			progress.inc_length(first_part.len() as u64);
+			let builder = self
+				.client
+				.put_object()
+				.key(key)
+				.bucket(&self.name)
+				.body(progress.wrap_body(ByteStream::from(first_part)))
+				.content_type(&content_type);
+			let builder = headers.apply_to_put(builder);
+			opts.sse.apply_to_put(builder).send().await?;
+		} else {
+			self
+				.upload_reader_multipart(
+					reader,
+					first_part,
+					key,
+					&content_type,
+					&headers,
+					&opts.sse,
+					&opts.multipart,
+					&progress,
+				)
+				.await?;
+		}
+
+		Ok(CpItem::new(
+			CpAction::Upload {
+				content_type,
+				content_encoding: None,
+				encrypted: false,
+			},
+			src,
+			dst,
+		))
+	}
+}
+
+/// Read up to `part_size` bytes (less only at the end of the stream).
+pub(super) async fn read_part(reader: &mut (impl AsyncRead + Unpin), part_size: u64) -> Result<Vec<u8>> {
+	let mut part = Vec::new();
+	(&mut *reader).take(part_size).read_to_end(&mut part).await?;
+	Ok(part)
+}
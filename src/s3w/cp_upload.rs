@@ -1,10 +1,12 @@
 use super::{Inex, SBucket, compute_dst_key, compute_inex};
-use crate::{Error, Result, s};
+use crate::{Error, Result};
 use aws_sdk_s3::primitives::ByteStream;
-use std::path::Path;
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
 // use tokio_stream::StreamExt;
 use crate::s3w::SItemsCache;
-use crate::s3w::support::{CpOptions, OverMode, validate_over_for_s3_dest};
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::support::{CpOptions, validate_over_for_s3_dest};
 use walkdir::WalkDir;
 
 /// "cp upload" Implementation
@@ -13,34 +15,58 @@ impl SBucket {
 	///
 	/// - IMPORANT - Right now, a leading '/' on prefix will be stripped and act if there are now. All prefix are from root.
 	///
+	/// - Files are uploaded up to `opts.concurrency` at a time, but reported in the walk order.
+	///   A failing file does not stop the others, and the failures are returned as a `CpTransfersFailed` error.
+	///
 	/// - TODO - add support for rename (when prefix has same extension as file and src_path is a file)
-	pub async fn upload_path(&self, src_path: impl AsRef<Path>, prefix: &str, opts: CpOptions) -> Result<()> {
-		let src_path = src_path.as_ref();
+	pub async fn upload_path(&self, src_path: impl AsRef<Path>, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		self.upload_path_report(src_path.as_ref(), prefix, opts).await?.into_result()
+	}
+
+	/// Same as `upload_path`, but the failures are kept in the report (e.g., for mv to still move the transferred files).
+	pub(super) async fn upload_path_report(&self, src_path: &Path, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		let mut report = CpReport::new(&opts.progress);
 
 		// When copy only a given file
 		if src_path.is_file() {
 			let key = compute_dst_key(None, src_path, prefix, true)?;
-			self.upload_file(src_path, &key, &opts, None).await?;
+			let res = self.upload_file(src_path, &key, &opts, None).await;
+			report.add(src_path.to_string_lossy(), self.s3_url(&key), res, opts.show_skip);
 		}
 		// When copying all file from a directory (recursive if opts.recursive)
 		else if src_path.is_dir() {
 			let max_depth = if opts.recursive { usize::MAX } else { 1 };
 			let walker = WalkDir::new(src_path).max_depth(max_depth).into_iter();
 
-			let sitems_cache = if matches!(opts.over, OverMode::Etag) {
+			let sitems_cache = if opts.over.compares_dst() {
 				Some(self.sitems_cache(Some(prefix)).await?)
 			} else {
 				None
 			};
 
+			// -- Compute the (file, key) to upload
+			let mut files_and_keys: Vec<(PathBuf, String)> = Vec::new();
 			for entry in walker.filter_map(|e| e.ok()) {
 				let file = entry.path();
 
 				if file.is_file() {
 					let key = compute_dst_key(Some(src_path), file, prefix, false)?;
-					self.upload_file(file, &key, &opts, sitems_cache.as_ref()).await?;
+					files_and_keys.push((file.to_path_buf(), key));
 				}
 			}
+
+			// -- Upload them (buffered to preserve the walk order)
+			let (opts_ref, sitems_cache) = (&opts, sitems_cache.as_ref());
+			let mut results = futures::stream::iter(files_and_keys)
+				.map(|(file, key)| async move {
+					let res = self.upload_file(&file, &key, opts_ref, sitems_cache).await;
+					(file, key, res)
+				})
+				.buffered(opts.concurrency());
+
+			while let Some((file, key, res)) = results.next().await {
+				report.add(file.to_string_lossy(), self.s3_url(&key), res, opts.show_skip);
+			}
 		}
 		// If not file or dir, we fail for now.
 		// TODO: Needs to decide what to do with symlink
@@ -48,45 +74,111 @@ impl SBucket {
 			return Err(Error::FilePathNotFound(src_path.to_string_lossy().to_string()));
 		}
 
-		Ok(())
+		Ok(report)
 	}
 
 	/// Lower level function that upload a single file to a fully resolved key
-	async fn upload_file(&self, src_file: &Path, key: &str, opts: &CpOptions, sitems_cache: Option<&SItemsCache>) -> Result<()> {
+	pub(super) async fn upload_file(
+		&self,
+		src_file: &Path,
+		key: &str,
+		opts: &CpOptions,
+		sitems_cache: Option<&SItemsCache>,
+	) -> Result<CpItem> {
 		// --- Make sure it is a file
 		if !src_file.is_file() {
 			panic!("CODE-ERROR - sbucket.upload_file should only get a file object. Code error.");
 		}
 
+		let src = src_file.to_string_lossy();
+		let dst = self.s3_url(key);
+
 		if let Some(file_name) = src_file.file_name().and_then(|f| f.to_str())
 			&& let Some(ignore_set) = &self.default_ignore_upload_names
 			&& ignore_set.contains(file_name)
 		{
-			if opts.show_skip {
-				println!("{:13} {file_name}", "Skip (default)");
-			}
+			return Ok(CpItem::new(CpAction::Skip { reason: "default" }, src, dst));
+		}
 
-			return Ok(());
+		// Note: Non unicode file paths are silently ignored for now
+		if src_file.to_str().is_none() {
+			return Ok(CpItem::new(CpAction::Ignore, src, dst));
 		}
 
-		if let Some(src_file_str) = src_file.to_str() {
-			match compute_inex(key, &opts.includes, &opts.excludes) {
-				Inex::Include => {
-					if validate_over_for_s3_dest(self, key, src_file, opts, sitems_cache).await? {
-						// BUILD - the src file info
-						let mime_type = match (&opts.noext_ct, src_file.extension()) {
-							(Some(noext_ct), None) => s!(noext_ct),
-							_ => mime_guess::from_path(src_file).first_or_octet_stream().to_string(),
-						};
-						let body = ByteStream::from_path(&src_file).await?;
-
-						println!(
-							"{:13} {:50} --> {}   (content-type: {})",
-							"Uploading",
-							src_file.display(),
-							self.s3_url(key),
-							mime_type
-						);
+		let item = match compute_inex(key, &opts.includes, &opts.excludes) {
+			Inex::Include => {
+				// Note: Compressed before the over validation only when it compares the uploaded content
+				//       (e.g., etag of the compressed bytes), otherwise only once the file is to be uploaded.
+				let compresses_first = opts.over.compares_content();
+				let mut compressed = match opts.compress {
+					Some(compress) if compresses_first => compress
+						.compress_file_blocking(src_file)
+						.await?
+						.map(|temp_file| (compress, temp_file)),
+					_ => None,
+				};
+				let content_file = compressed.as_ref().map(|(_, temp_file)| temp_file.path()).unwrap_or(src_file);
+
+				if validate_over_for_s3_dest(self, key, src_file, content_file, opts, sitems_cache).await? {
+					if let Some(compress) = opts.compress
+						&& !compresses_first
+					{
+						compressed = compress
+							.compress_file_blocking(src_file)
+							.await?
+							.map(|temp_file| (compress, temp_file));
+					}
+					let content_file = compressed.as_ref().map(|(_, temp_file)| temp_file.path()).unwrap_or(src_file);
+
+					// BUILD - the src file info (the content type stays the one of the src file when compressed)
+					let (content_type, mut headers) = opts.header_rules.resolve(key, opts.content_type.as_deref(), &opts.headers);
+					let mime_type = content_type.unwrap_or_else(|| mime_guess::from_path(src_file).first_or_octet_stream().to_string());
+					let content_encoding = compressed.as_ref().map(|(compress, _)| compress.content_encoding());
+					if let Some(content_encoding) = content_encoding {
+						headers.content_encoding = Some(content_encoding.to_string());
+					}
+
+					// Note: Encrypted last (after the compression), and not on dry run (the size is the one of the content)
+					let encrypted = match &opts.encrypt_key {
+						Some(encrypt_key) if !opts.dry_run => {
+							let (temp_file, envelope_metadata) = encrypt_key.encrypt_file_blocking(content_file).await?;
+							headers.metadata.get_or_insert_with(Default::default).extend(envelope_metadata);
+							Some(temp_file)
+						}
+						_ => None,
+					};
+					let content_file = encrypted.as_ref().map(|temp_file| temp_file.path()).unwrap_or(content_file);
+					let file_size = content_file.metadata()?.len();
+
+					if opts.dry_run {
+						return Ok(CpItem::new(
+							CpAction::DryRun {
+								verb: "upload",
+								size: file_size,
+							},
+							src,
+							dst,
+						));
+					}
+
+					let progress = opts.progress.start_file(&src, file_size);
+
+					// EXECUTE - multipart when above threshold, otherwise single put
+					if opts.multipart.is_multipart(file_size) {
+						self
+							.upload_file_multipart(
+								content_file,
+								key,
+								&mime_type,
+								&headers,
+								&opts.sse,
+								file_size,
+								&opts.multipart,
+								&progress,
+							)
+							.await?;
+					} else {
+						let body = progress.wrap_body(ByteStream::from_path(content_file).await?);
 
 						// BUILD - aws s3 put request
 						let builder = self
@@ -95,22 +187,34 @@ impl SBucket {
 							.key(key)
 							.bucket(&self.name)
 							.body(body)
-							.content_type(mime_type);
+							.content_type(&mime_type);
+						let builder = headers.apply_to_put(builder);
+						let builder = opts.sse.apply_to_put(builder);
 
 						// EXECUTE - aws request
 						builder.send().await?;
-					} else if opts.show_skip {
-						let msg = format!("Skip ({})", opts.over.label());
-						println!("{:13} - {}", msg, self.s3_url(key));
 					}
+
+					CpItem::new(
+						CpAction::Upload {
+							content_type: mime_type,
+							content_encoding,
+							encrypted: encrypted.is_some(),
+						},
+						src,
+						dst,
+					)
+					.with_size(file_size)
+				} else {
+					CpItem::new(CpAction::Skip { reason: opts.over.label() }, src, dst)
 				}
-				Inex::ExcludeInExclude => println!("{:20} {src_file_str}", "Excludes"),
-				// if exclude because not in include, then, quiet
-				Inex::ExcludeNotInInclude => (),
 			}
-		}
+			Inex::ExcludeInExclude => CpItem::new(CpAction::Exclude, src, dst),
+			// if exclude because not in include, then, quiet
+			Inex::ExcludeNotInInclude => CpItem::new(CpAction::Ignore, src, dst),
+		};
 
-		Ok(())
+		Ok(item)
 	}
 }
 
@@ -35,6 +35,23 @@ impl std::fmt::Debug for AwsCred {
 	}
 }
 
+impl AwsCred {
+	pub fn identity(&self) -> CredIdentity {
+		CredIdentity {
+			key_id: self.key_id.clone(),
+			endpoint: self.endpoint.clone(),
+		}
+	}
+}
+
+/// The non secret part of an AwsCred identifying the account/server a client talks to.
+/// Note: The region is not part of it, as a server side copy can be done across regions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredIdentity {
+	pub key_id: String,
+	pub endpoint: Option<String>,
+}
+
 enum CredKey {
 	Id,
 	Secret,
@@ -67,6 +84,7 @@ impl EnvType {
 	}
 }
 
+#[derive(Clone)]
 pub struct RegionProfile {
 	pub region: Option<String>,
 	pub profile: Option<String>,
@@ -120,7 +138,7 @@ pub fn client_from_cred(aws_cred: AwsCred) -> Result<Client> {
 ///    - try SS3_BUCKET_... envs
 ///    - try the default AWS env keys
 ///    - if still not found, error
-async fn load_aws_cred(reg_pro: RegionProfile, bucket: Option<&str>) -> Result<AwsCred> {
+pub(super) async fn load_aws_cred(reg_pro: RegionProfile, bucket: Option<&str>) -> Result<AwsCred> {
 	let mut cred_result: Option<AwsCred> = None;
 
 	// TODO: Need to determine if we need to check if we have a profile first before doing the bucket load.
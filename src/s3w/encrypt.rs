@@ -0,0 +1,386 @@
+//! The client side encryption of the uploads (`cp --encrypt-key-file`), and the decryption of the downloads.
+//!
+//! - Envelope encryption: each object has its own random data key, wrapped (AES-256-GCM) with the key of the key file.
+//! - The file is encrypted by chunks (AES-256-GCM) to a temp file, which is then uploaded in place of the file.
+//!   The nonce of a chunk is made of the object nonce prefix, the chunk index, and a last chunk flag,
+//!   so that reordered, dropped, or truncated chunks fail the decryption (as any modified byte does).
+//! - The envelope parameters are stored in the object metadata (`x-amz-meta-ss3-enc-...`), and authenticated with the data key.
+//! - The downloads of such objects are decrypted through a temp file, which becomes the destination file only once fully authenticated.
+//!
+//! Note: The encryption is randomized, so the etag/size over modes cannot be used with it (rejected with the cp options).
+
+use crate::s3w::ObjectHeaders;
+use crate::s3w::support::read_256_bit_key_file;
+use crate::{Error, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, Nonce, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// The envelope algorithm (AES-256-GCM data key wrapping, and AES-256-GCM chunks)
+const ENC_ALGORITHM: &str = "AES256-GCM-CHUNKED-V1";
+
+/// The metadata keys of the envelope (all starting with `META_ALGORITHM`)
+const META_ALGORITHM: &str = "ss3-enc";
+const META_DATA_KEY: &str = "ss3-enc-key";
+const META_NONCE: &str = "ss3-enc-nonce";
+const META_CHUNK_SIZE: &str = "ss3-enc-chunk-size";
+const META_SIZE: &str = "ss3-enc-size";
+
+/// The key size (256 bits), the nonce size, and the authentication tag size of AES-256-GCM
+const ENC_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// The object nonce prefix size (the rest of a chunk nonce is its u32 index and its last chunk flag)
+const NONCE_PREFIX_LEN: usize = 7;
+
+/// The plain size of the chunks (the last one might be smaller)
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The max chunk size accepted from the metadata (to not allocate whatever a tampered metadata says)
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+// region:    --- EncryptKey
+
+/// The 256-bit key of the key file, wrapping the data keys of the encrypted objects
+#[derive(Clone)]
+pub struct EncryptKey {
+	key: [u8; ENC_KEY_LEN],
+}
+
+// implement Debug but redact the key
+impl fmt::Debug for EncryptKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "EncryptKey {{ key: REDACTED }}")
+	}
+}
+
+impl EncryptKey {
+	/// The key of `--encrypt-key-file`, a file of the raw 32 bytes, or of the base64 encoded key.
+	/// Note: The errors never contain the key material.
+	pub fn from_file(path: &Path) -> Result<Self> {
+		Ok(EncryptKey {
+			key: read_256_bit_key_file(path)?,
+		})
+	}
+
+	/// Same as `encrypt_file`, but on the blocking threads (so that a big file does not stall the other transfers).
+	pub(super) async fn encrypt_file_blocking(&self, src_file: &Path) -> Result<(NamedTempFile, HashMap<String, String>)> {
+		let (encrypt_key, src_file) = (self.clone(), src_file.to_path_buf());
+		tokio::task::spawn_blocking(move || encrypt_key.encrypt_file(&src_file)).await?
+	}
+
+	/// Encrypt `src_file` into a temp file (deleted when dropped).
+	/// Returns it with the envelope metadata to be stored with the object.
+	pub(super) fn encrypt_file(&self, src_file: &Path) -> Result<(NamedTempFile, HashMap<String, String>)> {
+		let data_key = Aes256Gcm::generate_key(OsRng);
+		let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+		OsRng.fill_bytes(&mut nonce_prefix);
+
+		let mut envelope = Envelope {
+			nonce_prefix,
+			chunk_size: CHUNK_SIZE,
+			size: src_file.metadata()?.len(),
+			wrapped_key: Vec::new(),
+		};
+		envelope.wrapped_key = self.wrap_data_key(&data_key, &envelope)?;
+
+		let cipher = Aes256Gcm::new(&data_key);
+		let mut reader = BufReader::new(File::open(src_file)?);
+		let mut temp_file = NamedTempFile::new()?;
+
+		{
+			let mut writer = BufWriter::new(temp_file.as_file_mut());
+			let mut buf = vec![0u8; envelope.chunk_size];
+			let chunk_count = envelope.chunk_count()?;
+			for index in 0..chunk_count {
+				let len = envelope.chunk_len(index, chunk_count);
+				reader.read_exact(&mut buf[..len])?;
+				let encrypted = cipher
+					.encrypt(&envelope.chunk_nonce(index, chunk_count), &buf[..len])
+					.map_err(|_| Error::Static("CODE-ERROR - AES-256-GCM chunk encryption failed"))?;
+				writer.write_all(&encrypted)?;
+			}
+			writer.flush()?;
+		}
+
+		Ok((temp_file, envelope.to_metadata()))
+	}
+
+	/// Returns the nonce followed by the encrypted data key (authenticating the envelope parameters)
+	fn wrap_data_key(&self, data_key: &Key<Aes256Gcm>, envelope: &Envelope) -> Result<Vec<u8>> {
+		let nonce = Aes256Gcm::generate_nonce(OsRng);
+		let aad = envelope.aad();
+		let payload = Payload {
+			msg: data_key.as_slice(),
+			aad: aad.as_bytes(),
+		};
+		let encrypted = self
+			.cipher()
+			.encrypt(&nonce, payload)
+			.map_err(|_| Error::Static("CODE-ERROR - AES-256-GCM data key encryption failed"))?;
+
+		Ok([nonce.as_slice(), &encrypted].concat())
+	}
+
+	/// Returns None when the key is not the one of the object, or the envelope was tampered with
+	fn unwrap_data_key(&self, envelope: &Envelope) -> Option<Key<Aes256Gcm>> {
+		if envelope.wrapped_key.len() < NONCE_LEN {
+			return None;
+		}
+		let (nonce, encrypted) = envelope.wrapped_key.split_at(NONCE_LEN);
+		let aad = envelope.aad();
+		let payload = Payload {
+			msg: encrypted,
+			aad: aad.as_bytes(),
+		};
+		let data_key = self.cipher().decrypt(Nonce::<Aes256Gcm>::from_slice(nonce), payload).ok()?;
+
+		(data_key.len() == ENC_KEY_LEN).then(|| *Key::<Aes256Gcm>::from_slice(&data_key))
+	}
+
+	fn cipher(&self) -> Aes256Gcm {
+		Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+	}
+}
+
+// endregion: --- EncryptKey
+
+// region:    --- Envelope
+
+/// The encryption parameters of an object (from/to its metadata)
+#[derive(Debug)]
+struct Envelope {
+	nonce_prefix: [u8; NONCE_PREFIX_LEN],
+	/// The plain size of the chunks
+	chunk_size: usize,
+	/// The plain size of the object
+	size: u64,
+	/// The nonce followed by the encrypted data key
+	wrapped_key: Vec<u8>,
+}
+
+impl Envelope {
+	/// Returns None when the metadata has no envelope (not client side encrypted).
+	fn from_metadata(key: &str, metadata: Option<&HashMap<String, String>>) -> Result<Option<Envelope>> {
+		let Some(metadata) = metadata else {
+			return Ok(None);
+		};
+		let Some(algorithm) = metadata.get(META_ALGORITHM) else {
+			return Ok(None);
+		};
+
+		let invalid = |cause: &'static str| Error::ObjectDecryptFailed {
+			key: key.to_string(),
+			cause,
+		};
+		if algorithm != ENC_ALGORITHM {
+			return Err(invalid("unknown encryption algorithm"));
+		}
+
+		let value_of = |name: &str| metadata.get(name).ok_or_else(|| invalid("missing encryption metadata"));
+		let nonce_prefix = BASE64
+			.decode(value_of(META_NONCE)?)
+			.ok()
+			.and_then(|nonce| nonce.try_into().ok())
+			.ok_or_else(|| invalid("invalid encryption nonce"))?;
+		let wrapped_key = BASE64
+			.decode(value_of(META_DATA_KEY)?)
+			.map_err(|_| invalid("invalid encryption data key"))?;
+		let chunk_size = value_of(META_CHUNK_SIZE)?
+			.parse::<usize>()
+			.ok()
+			.filter(|size| (1..=MAX_CHUNK_SIZE).contains(size))
+			.ok_or_else(|| invalid("invalid encryption chunk size"))?;
+		let size = value_of(META_SIZE)?
+			.parse::<u64>()
+			.map_err(|_| invalid("invalid encryption size"))?;
+
+		Ok(Some(Envelope {
+			nonce_prefix,
+			chunk_size,
+			size,
+			wrapped_key,
+		}))
+	}
+
+	fn to_metadata(&self) -> HashMap<String, String> {
+		HashMap::from([
+			(META_ALGORITHM.to_string(), ENC_ALGORITHM.to_string()),
+			(META_DATA_KEY.to_string(), BASE64.encode(&self.wrapped_key)),
+			(META_NONCE.to_string(), BASE64.encode(self.nonce_prefix)),
+			(META_CHUNK_SIZE.to_string(), self.chunk_size.to_string()),
+			(META_SIZE.to_string(), self.size.to_string()),
+		])
+	}
+
+	/// The envelope parameters, authenticated with the wrapped data key
+	fn aad(&self) -> String {
+		format!(
+			"{ENC_ALGORITHM}|{}|{}|{}",
+			BASE64.encode(self.nonce_prefix),
+			self.chunk_size,
+			self.size
+		)
+	}
+
+	/// Note: An empty file still has one (empty) chunk, so that its truncation is detected as well.
+	fn chunk_count(&self) -> Result<u32> {
+		let count = self.size.div_ceil(self.chunk_size as u64).max(1);
+		u32::try_from(count).map_err(|_| Error::Static("Too many encryption chunks (file too large for its chunk size)"))
+	}
+
+	/// The plain size of the chunk at `index`
+	fn chunk_len(&self, index: u32, chunk_count: u32) -> usize {
+		if index + 1 < chunk_count {
+			self.chunk_size
+		} else {
+			(self.size - self.chunk_size as u64 * index as u64) as usize
+		}
+	}
+
+	fn chunk_nonce(&self, index: u32, chunk_count: u32) -> Nonce<Aes256Gcm> {
+		let last_flag = u8::from(index + 1 == chunk_count);
+		let nonce: Vec<u8> = [&self.nonce_prefix[..], &index.to_be_bytes(), &[last_flag]].concat();
+		*Nonce::<Aes256Gcm>::from_slice(&nonce)
+	}
+}
+
+// endregion: --- Envelope
+
+// region:    --- Decryption
+
+/// The decryption of a downloaded client side encrypted object
+/// (the object is downloaded to the encrypted temp file, and then decrypted to the destination file).
+pub(super) struct Decryption {
+	envelope: Envelope,
+	encrypt_key: EncryptKey,
+	encrypted_file: NamedTempFile,
+}
+
+impl Decryption {
+	/// Returns the decryption of the object when its metadata has an envelope (None otherwise).
+	/// The encrypted temp file is created next to `dst_file` (so that the decrypted file can be renamed to it).
+	///
+	/// Note: Fails when the object is encrypted but no key is given (the object is not downloaded as is).
+	pub(super) fn for_object(
+		key: &str,
+		metadata: Option<&HashMap<String, String>>,
+		encrypt_key: Option<&EncryptKey>,
+		dst_file: &Path,
+	) -> Result<Option<Self>> {
+		let Some(envelope) = Envelope::from_metadata(key, metadata)? else {
+			return Ok(None);
+		};
+		let encrypt_key = encrypt_key
+			.cloned()
+			.ok_or_else(|| Error::ObjectEncryptKeyRequired { key: key.to_string() })?;
+
+		Ok(Some(Decryption {
+			envelope,
+			encrypt_key,
+			encrypted_file: NamedTempFile::new_in(dir_of(dst_file))?,
+		}))
+	}
+
+	/// The file to download the encrypted object to
+	pub(super) fn encrypted_file(&self) -> &Path {
+		self.encrypted_file.path()
+	}
+
+	/// Same as `decrypt_to`, but on the blocking threads (so that a big object does not stall the other transfers).
+	pub(super) async fn decrypt_to_blocking(self, key: &str, dst_file: &Path) -> Result<()> {
+		let (key, dst_file) = (key.to_string(), dst_file.to_path_buf());
+		tokio::task::spawn_blocking(move || self.decrypt_to(&key, &dst_file)).await?
+	}
+
+	/// Decrypt the downloaded encrypted file to `dst_file`.
+	/// The chunks are decrypted to a temp file, which replaces `dst_file` only once all of them are authenticated.
+	pub(super) fn decrypt_to(self, key: &str, dst_file: &Path) -> Result<()> {
+		let failed = |cause: &'static str| Error::ObjectDecryptFailed {
+			key: key.to_string(),
+			cause,
+		};
+		let envelope = &self.envelope;
+
+		let data_key = self
+			.encrypt_key
+			.unwrap_data_key(envelope)
+			.ok_or_else(|| failed("the data key cannot be decrypted"))?;
+		let cipher = Aes256Gcm::new(&data_key);
+
+		// -- Check the encrypted size (each chunk has its tag)
+		let chunk_count = envelope.chunk_count()?;
+		let encrypted_size = envelope.size + chunk_count as u64 * TAG_LEN as u64;
+		if self.encrypted_file.as_file().metadata()?.len() != encrypted_size {
+			return Err(failed("unexpected encrypted size"));
+		}
+
+		// -- Decrypt the chunks
+		let mut reader = BufReader::new(File::open(self.encrypted_file.path())?);
+		let mut decrypted_file = NamedTempFile::new_in(dir_of(dst_file))?;
+		{
+			let mut writer = BufWriter::new(decrypted_file.as_file_mut());
+			let mut buf = vec![0u8; envelope.chunk_size + TAG_LEN];
+			for index in 0..chunk_count {
+				let len = envelope.chunk_len(index, chunk_count) + TAG_LEN;
+				reader.read_exact(&mut buf[..len])?;
+				let decrypted = cipher
+					.decrypt(&envelope.chunk_nonce(index, chunk_count), &buf[..len])
+					.map_err(|_| failed("a chunk does not authenticate"))?;
+				writer.write_all(&decrypted)?;
+			}
+			writer.flush()?;
+		}
+
+		decrypted_file.persist(dst_file).map_err(|err| Error::IO(err.error))?;
+
+		Ok(())
+	}
+}
+
+/// Returns true if the object metadata has an envelope (client side encrypted)
+pub(super) fn is_client_encrypted(metadata: Option<&HashMap<String, String>>) -> bool {
+	metadata.is_some_and(|metadata| metadata.contains_key(META_ALGORITHM))
+}
+
+/// Returns the headers of a copy/relay with the envelope metadata of the source object, if any
+/// (so that a copy of an encrypted object can still be decrypted, even when its metadata is replaced or not carried).
+pub(super) fn with_src_envelope(headers: &ObjectHeaders, src_metadata: Option<&HashMap<String, String>>) -> ObjectHeaders {
+	let mut headers = headers.clone();
+	let envelope = src_metadata
+		.into_iter()
+		.flatten()
+		.filter(|(name, _)| name.starts_with(META_ALGORITHM))
+		.map(|(name, val)| (name.clone(), val.clone()));
+	for (name, val) in envelope {
+		headers.metadata.get_or_insert_with(HashMap::new).insert(name, val);
+	}
+	headers
+}
+
+/// Note: A relative file name has an empty parent (the current dir).
+fn dir_of(file: &Path) -> &Path {
+	match file.parent() {
+		Some(dir) if !dir.as_os_str().is_empty() => dir,
+		_ => Path::new("."),
+	}
+}
+
+// endregion: --- Decryption
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-encrypt.rs"]
+mod tests;
+
+// endregion: --- Tests
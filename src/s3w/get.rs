@@ -1,6 +1,11 @@
 use super::SBucket;
 use super::sitem::SItem;
+use crate::s3w::ObjectSse;
 use crate::{Error, Result};
+use aws_sdk_s3::error::SdkError;
+use aws_sdk_s3::operation::get_object::GetObjectError;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::primitives::ByteStream;
 
 impl SBucket {
 	pub async fn get_sitem(&self, key: &str) -> Result<SItem> {
@@ -14,4 +19,67 @@ impl SBucket {
 
 		Ok(SItem::from_object(obj))
 	}
+
+	/// Get the SItem of an object from a head request (exact key match, unlike `get_sitem`)
+	pub async fn head_sitem(&self, key: &str) -> Result<SItem> {
+		self.head_sitem_sse(key, &ObjectSse::default()).await
+	}
+
+	/// Same as `head_sitem`, with the SSE-C key of the object (if any)
+	pub async fn head_sitem_sse(&self, key: &str, sse: &ObjectSse) -> Result<SItem> {
+		let head = self.head_object_sse(key, sse).await?;
+		Ok(SItem::from_head(key, &head))
+	}
+
+	/// The head of an object, with the SSE-C key of the object (if any)
+	pub(super) async fn head_object_sse(&self, key: &str, sse: &ObjectSse) -> Result<HeadObjectOutput> {
+		let builder = self.client.head_object().bucket(&self.name).key(key);
+		match sse.apply_to_head(builder).send().await {
+			Ok(head) => Ok(head),
+			Err(err) if err.as_service_error().is_some_and(|e| e.is_not_found()) => Err(Error::S3ObjectNotFound { key: key.to_string() }),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	/// Get the `offset..offset + length` byte range of an object, fully read in memory (so, meant for part size ranges).
+	/// Note: When the etag is known, the request is conditional (if-match) so that an object changed in between fails.
+	pub(super) async fn get_range_body(
+		&self,
+		key: &str,
+		etag: Option<&str>,
+		offset: u64,
+		length: u64,
+		sse: &ObjectSse,
+	) -> Result<ByteStream> {
+		let builder = self
+			.client
+			.get_object()
+			.bucket(&self.name)
+			.key(key)
+			.range(format!("bytes={offset}-{}", offset + length - 1))
+			.set_if_match(etag.map(|etag| format!("\"{etag}\"")));
+		let resp = sse.apply_to_get(builder).send().await.map_err(|err| get_object_error(key, err))?;
+
+		let data = resp.body.collect().await?.into_bytes();
+		if data.len() as u64 != length {
+			return Err(Error::DownloadSizeMismatch {
+				key: key.to_string(),
+				expected: length,
+				actual: data.len() as u64,
+			});
+		}
+
+		Ok(ByteStream::from(data))
+	}
+}
+
+/// Map a get object error, with the archived objects (e.g., GLACIER, DEEP_ARCHIVE, not restored) as `Error::ObjectArchived`
+pub(super) fn get_object_error(key: &str, err: SdkError<GetObjectError>) -> Error {
+	match err.as_service_error() {
+		Some(GetObjectError::InvalidObjectState(state)) => Error::ObjectArchived {
+			key: key.to_string(),
+			storage_class: state.storage_class().map(|sc| sc.as_str()).unwrap_or("unknown").to_string(),
+		},
+		_ => err.into(),
+	}
 }
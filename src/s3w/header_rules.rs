@@ -0,0 +1,179 @@
+//! The per glob header rules of the uploads (`--headers-file rules.toml`).
+//!
+//! ```toml
+//! [[rules]]
+//! glob = "*.html"
+//! cache-control = "no-cache"
+//!
+//! [[rules]]
+//! glob = "**/assets/**"
+//! cache-control = "public, max-age=31536000, immutable"
+//! metadata = { release = "1.2.0" }
+//!
+//! [[rules]]
+//! glob = "**/archives/**"
+//! storage-class = "GLACIER_IR"
+//! ```
+//!
+//! - The globs are matched against the object key (like `--include` / `--exclude`).
+//! - All the matching rules are applied in the file order (a later rule overrides the headers of an earlier one, and the metadata are merged).
+//! - The rules take precedence over the command line headers, which stay the defaults of the keys not matched.
+
+use crate::s3w::{ObjectHeaders, parse_storage_class};
+use crate::{Error, Result};
+use aws_sdk_s3::types::StorageClass;
+use globset::{Glob, GlobMatcher};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// region:    --- HeaderRules
+
+#[derive(Debug, Default, Clone)]
+pub struct HeaderRules {
+	rules: Vec<HeaderRule>,
+}
+
+#[derive(Debug, Clone)]
+struct HeaderRule {
+	matcher: RuleMatcher,
+	content_type: Option<String>,
+	cache_control: Option<String>,
+	content_encoding: Option<String>,
+	metadata: Option<HashMap<String, String>>,
+	storage_class: Option<StorageClass>,
+}
+
+#[derive(Debug, Clone)]
+enum RuleMatcher {
+	Glob(GlobMatcher),
+	/// The keys without file extension (`--noext-ct`)
+	NoExtension,
+}
+
+impl HeaderRules {
+	pub fn from_file(path: &Path) -> Result<Self> {
+		let invalid = |cause: String| Error::HeadersFileInvalid {
+			path: path.display().to_string(),
+			cause,
+		};
+
+		let content = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+		let file: RulesFile = toml::from_str(&content).map_err(|err| invalid(err.to_string()))?;
+
+		let rules = file
+			.rules
+			.into_iter()
+			.map(|entry| {
+				let glob = Glob::new(&entry.glob).map_err(|err| invalid(err.to_string()))?;
+				let storage_class = entry
+					.storage_class
+					.map(|val| parse_storage_class(&val))
+					.transpose()
+					.map_err(|err| invalid(err.to_string()))?;
+				Ok(HeaderRule {
+					matcher: RuleMatcher::Glob(glob.compile_matcher()),
+					content_type: entry.content_type,
+					cache_control: entry.cache_control,
+					content_encoding: entry.content_encoding,
+					metadata: entry.metadata,
+					storage_class,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(HeaderRules { rules })
+	}
+
+	/// Add the content type rule of the keys without file extension (`--noext-ct`).
+	/// Note: Added first, so that the rules of the file take precedence over it.
+	///       Also, the command line `--content-type` takes precedence over it (see `resolve`).
+	pub fn with_noext_ct(mut self, content_type: String) -> Self {
+		self.rules.insert(
+			0,
+			HeaderRule {
+				matcher: RuleMatcher::NoExtension,
+				content_type: Some(content_type),
+				cache_control: None,
+				content_encoding: None,
+				metadata: None,
+				storage_class: None,
+			},
+		);
+		self
+	}
+
+	/// Returns the content type and the headers of the upload of `key`,
+	/// from the matching rules applied over the command line ones (`content_type`, `headers`).
+	///
+	/// Note: The `--noext-ct` rule is only a default, so it does not override the command line content type.
+	pub(super) fn resolve(&self, key: &str, content_type: Option<&str>, headers: &ObjectHeaders) -> (Option<String>, ObjectHeaders) {
+		let has_cmd_content_type = content_type.is_some();
+		let mut content_type = content_type.map(|ct| ct.to_string());
+		let mut headers = headers.clone();
+
+		for rule in self.rules.iter().filter(|rule| rule.matches(key)) {
+			if let Some(rule_content_type) = &rule.content_type
+				&& !(has_cmd_content_type && matches!(rule.matcher, RuleMatcher::NoExtension))
+			{
+				content_type = Some(rule_content_type.clone());
+			}
+			if let Some(cache_control) = &rule.cache_control {
+				headers.cache_control = Some(cache_control.clone());
+			}
+			if let Some(content_encoding) = &rule.content_encoding {
+				headers.content_encoding = Some(content_encoding.clone());
+			}
+			if let Some(metadata) = &rule.metadata {
+				headers.metadata.get_or_insert_with(HashMap::new).extend(metadata.clone());
+			}
+			if let Some(storage_class) = &rule.storage_class {
+				headers.storage_class = Some(storage_class.clone());
+			}
+		}
+
+		(content_type, headers)
+	}
+}
+
+impl HeaderRule {
+	fn matches(&self, key: &str) -> bool {
+		match &self.matcher {
+			RuleMatcher::Glob(glob) => glob.is_match(key),
+			RuleMatcher::NoExtension => Path::new(key).extension().is_none(),
+		}
+	}
+}
+
+// endregion: --- HeaderRules
+
+// region:    --- Rules File
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RulesFile {
+	#[serde(default)]
+	rules: Vec<RuleEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RuleEntry {
+	glob: String,
+	content_type: Option<String>,
+	cache_control: Option<String>,
+	content_encoding: Option<String>,
+	metadata: Option<HashMap<String, String>>,
+	storage_class: Option<String>,
+}
+
+// endregion: --- Rules File
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-header-rules.rs"]
+mod tests;
+
+// endregion: --- Tests
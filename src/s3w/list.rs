@@ -17,6 +17,8 @@ pub struct ListOptions {
 	pub includes: Option<GlobSet>,
 	pub continuation_token: Option<String>,
 	pub info: Option<ListInfo>,
+	/// Also get the owner of the objects (e.g., for the long listing)
+	pub fetch_owner: bool,
 }
 
 impl ListOptions {
@@ -74,6 +76,10 @@ impl SBucket {
 			builder = builder.delimiter("/");
 		}
 
+		if options.fetch_owner {
+			builder = builder.fetch_owner(true);
+		}
+
 		// EXECUTE - the AWS S3 request
 		let resp = match builder.send().await {
 			Ok(resp) => resp,
@@ -113,9 +119,33 @@ impl SBucket {
 		})
 	}
 
+	/// List all the objects of a prefix, following the continuation tokens across all pages.
+	/// Note: The prefixes are not returned (use `list` for the folder like listing).
+	pub async fn list_all(&self, prefix: &str, mut options: ListOptions) -> Result<Vec<SItem>> {
+		let mut all_objects: Vec<SItem> = Vec::new();
+		options.continuation_token = None;
+
+		loop {
+			let ListResult {
+				objects,
+				next_continuation_token,
+				..
+			} = self.list(prefix, &options).await?;
+			all_objects.extend(objects);
+
+			match next_continuation_token {
+				Some(token) => options.continuation_token = Some(token),
+				None => break,
+			}
+		}
+
+		Ok(all_objects)
+	}
+
+	/// Build the cache of all the objects of a prefix (all pages).
 	pub async fn sitems_cache(&self, prefix: Option<&str>) -> Result<SItemsCache> {
-		let res = self.list(prefix.unwrap_or_default(), &ListOptions::new(true)).await?;
+		let sitems = self.list_all(prefix.unwrap_or_default(), ListOptions::new(true)).await?;
 
-		Ok(SItemsCache::from(res.objects))
+		Ok(SItemsCache::from(sitems))
 	}
 }
@@ -5,29 +5,51 @@
 // -- Sub-modules
 
 mod clean;
+mod compress;
+mod cp_copy;
 mod cp_download;
+mod cp_report;
+mod cp_stdio;
 mod cp_upload;
+mod encrypt;
 mod get;
+mod header_rules;
 mod list;
+mod multipart;
+mod mv;
+mod progress;
+mod ranged_download;
+mod rb;
+mod restore;
 mod rm;
 mod sbucket;
 mod sitem;
+mod sse;
 mod support;
+mod sync;
 
 // -- Re-exports
 pub use self::bucket_ops::{create_bucket, delete_bucket, list_buckets};
 pub use self::cred::{AwsCred, RegionProfile, new_s3_client};
+pub use self::header_rules::HeaderRules;
 pub use self::list::*;
+pub use self::progress::Progress;
 pub use self::sbucket::{SBucket, SBucketConfig};
-pub use self::sitem::SItem;
-pub use crate::s3w::support::{CpOptions, OverMode};
+pub use self::sitem::{SItem, SItemType};
+pub use crate::s3w::support::{CpOptions, MultipartOptions, ObjectHeaders, OverMode, parse_storage_class};
 pub use clean::CleanOptions;
+pub use compress::CompressMode;
+pub use encrypt::EncryptKey;
+pub use restore::{DEFAULT_RESTORE_DAYS, RestoreOptions, RestoreStatus};
+pub use rm::RmOptions;
+pub use sse::{ObjectSse, SseCustomerKey};
+pub use sync::SyncOptions;
 
 pub mod bucket_ops;
 pub mod cred;
 
 // -- Imports
-use crate::s3w::cred::client_from_cred;
+use crate::s3w::cred::{CredIdentity, client_from_cred, load_aws_cred};
 use crate::{DEFAULT_UPLOAD_IGNORE_FILES, Error, Result};
 use aws_sdk_s3::Client;
 use globset::GlobSet;
@@ -40,20 +62,21 @@ use std::path::{Path, PathBuf};
 // region:    --- SBucket factory
 
 pub async fn get_sbucket(reg_pro: RegionProfile, bucket: &str) -> Result<SBucket> {
-	let client = new_s3_client(reg_pro, Some(bucket)).await?;
-	get_sbucket_from_client(client, bucket).await
+	let cred = load_aws_cred(reg_pro, Some(bucket)).await?;
+	get_sbucket_from_cred(cred, bucket).await
 }
 
-#[allow(unused)]
 pub async fn get_sbucket_from_cred(cred: AwsCred, bucket: &str) -> Result<SBucket> {
+	let cred_identity = cred.identity();
 	let client = client_from_cred(cred)?;
-	get_sbucket_from_client(client, bucket).await
+	get_sbucket_from_client(client, bucket, Some(cred_identity)).await
 }
 
-async fn get_sbucket_from_client(client: Client, bucket: impl Into<String>) -> Result<SBucket> {
+async fn get_sbucket_from_client(client: Client, bucket: impl Into<String>, cred_identity: Option<CredIdentity>) -> Result<SBucket> {
 	let default_ignore_files = HashSet::from_iter(DEFAULT_UPLOAD_IGNORE_FILES.map(String::from));
 	let config = SBucketConfig {
 		default_ignore_upload_names: Some(default_ignore_files),
+		cred_identity,
 	};
 	let sbucket = SBucket::from_client_and_name(client, bucket.into(), Some(config));
 	Ok(sbucket)
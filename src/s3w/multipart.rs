@@ -0,0 +1,302 @@
+use super::SBucket;
+use crate::s3w::ObjectSse;
+use crate::s3w::cp_stdio::read_part;
+use crate::s3w::progress::FileProgress;
+use crate::s3w::support::{MultipartOptions, ObjectHeaders, copy_source};
+use crate::{Error, Result};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::primitives::Length;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use futures::{StreamExt, TryStreamExt};
+use std::path::Path;
+use tokio::io::AsyncRead;
+
+/// "multipart upload" Implementation
+impl SBucket {
+	/// Upload a file with the S3 multipart API (up to `multipart.concurrency` parts at a time).
+	///
+	/// If any part (or the completion) fails, the upload is aborted so that S3 does not keep the orphan parts.
+	#[allow(clippy::too_many_arguments)]
+	pub(super) async fn upload_file_multipart(
+		&self,
+		src_file: &Path,
+		key: &str,
+		content_type: &str,
+		headers: &ObjectHeaders,
+		sse: &ObjectSse,
+		file_size: u64,
+		multipart: &MultipartOptions,
+		progress: &FileProgress,
+	) -> Result<()> {
+		let upload_id = self.create_multipart_upload(key, Some(content_type), headers, sse).await?;
+
+		// Note: buffered (rather than buffer_unordered) to keep the completed parts in part number order
+		let parts_res = futures::stream::iter(part_ranges(file_size, multipart.part_size_for(file_size)))
+			.map(|(part_number, offset, length)| self.upload_file_part(src_file, key, &upload_id, part_number, offset, length, sse, progress))
+			.buffered(multipart.concurrency)
+			.try_collect()
+			.await;
+
+		self.complete_or_abort_multipart_upload(key, &upload_id, parts_res).await
+	}
+
+	/// Upload a stream of unknown length (e.g., stdin) with the S3 multipart API, starting with its already read `first_part`.
+	///
+	/// The parts (of `multipart.part_size`) are uploaded as they are read, up to `multipart.concurrency` at a time (so held in memory).
+	/// Note: The S3 max part count (10,000) limits the stream size (e.g., ~78GB for 8MB parts), use a larger `--part-size` for more.
+	#[allow(clippy::too_many_arguments)]
+	pub(super) async fn upload_reader_multipart(
+		&self,
+		reader: impl AsyncRead + Unpin,
+		first_part: Vec<u8>,
+		key: &str,
+		content_type: &str,
+		headers: &ObjectHeaders,
+		sse: &ObjectSse,
+		multipart: &MultipartOptions,
+		progress: &FileProgress,
+	) -> Result<()> {
+		let upload_id = self.create_multipart_upload(key, Some(content_type), headers, sse).await?;
+		let upload_id_ref = upload_id.as_str();
+		let part_size = multipart.part_size;
+
+		// the parts as they are read (the first one already read), until the end of the stream
+		let parts = futures::stream::try_unfold(
+			(reader, Some(first_part), 1),
+			move |(mut reader, first_part, part_number)| async move {
+				let part = match first_part {
+					Some(part) => part,
+					None => read_part(&mut reader, part_size).await?,
+				};
+				if part.is_empty() {
+					return Ok(None);
+				}
+				progress.inc_length(part.len() as u64);
+				Ok(Some(((part_number, part), (reader, None, part_number + 1))))
+			},
+		);
+
+		let parts_res = parts
+			.map_ok(|(part_number, part)| self.upload_part(key, upload_id_ref, part_number, progress.wrap_body(ByteStream::from(part)), sse))
+			.try_buffered(multipart.concurrency)
+			.try_collect()
+			.await;
+
+		self.complete_or_abort_multipart_upload(key, &upload_id, parts_res).await
+	}
+
+	/// Copy an object (server side) with the S3 multipart API (`upload_part_copy`), required for objects above 5GB.
+	///
+	/// Note: Unlike `copy_object`, the multipart copy does not carry the source metadata, so the content type is given.
+	#[allow(clippy::too_many_arguments)]
+	pub(super) async fn copy_object_multipart(
+		&self,
+		src_bucket: &str,
+		src_key: &str,
+		key: &str,
+		size: u64,
+		content_type: Option<&str>,
+		headers: &ObjectHeaders,
+		sse: &ObjectSse,
+		multipart: &MultipartOptions,
+	) -> Result<()> {
+		let upload_id = self.create_multipart_upload(key, content_type, headers, sse).await?;
+		let copy_source = copy_source(src_bucket, src_key);
+
+		let parts_res = futures::stream::iter(part_ranges(size, multipart.part_size_for(size)))
+			.map(|(part_number, offset, length)| self.upload_part_copy(&copy_source, key, &upload_id, part_number, offset, length, sse))
+			.buffered(multipart.concurrency)
+			.try_collect()
+			.await;
+
+		self.complete_or_abort_multipart_upload(key, &upload_id, parts_res).await
+	}
+
+	/// Relay an object from a bucket with a different credential/endpoint (no server side copy possible) with the S3 multipart API.
+	///
+	/// Each part is downloaded by range and uploaded from memory, so at most `multipart.concurrency` parts are held in memory.
+	/// Note: When the source etag is known, the range requests are conditional (if-match), so a source changed during the relay fails.
+	#[allow(clippy::too_many_arguments)]
+	pub(super) async fn relay_object_multipart(
+		&self,
+		src_bucket: &SBucket,
+		src_key: &str,
+		src_etag: Option<&str>,
+		key: &str,
+		size: u64,
+		content_type: Option<&str>,
+		headers: &ObjectHeaders,
+		sse: &ObjectSse,
+		multipart: &MultipartOptions,
+	) -> Result<()> {
+		let upload_id = self.create_multipart_upload(key, content_type, headers, sse).await?;
+		let upload_id_ref = upload_id.as_str();
+
+		let parts_res = futures::stream::iter(part_ranges(size, multipart.part_size_for(size)))
+			.map(|(part_number, offset, length)| async move {
+				let body = src_bucket.get_range_body(src_key, src_etag, offset, length, sse).await?;
+				self.upload_part(key, upload_id_ref, part_number, body, sse).await
+			})
+			.buffered(multipart.concurrency)
+			.try_collect()
+			.await;
+
+		self.complete_or_abort_multipart_upload(key, &upload_id, parts_res).await
+	}
+}
+
+// region:    --- Multipart Steps
+
+impl SBucket {
+	async fn create_multipart_upload(
+		&self,
+		key: &str,
+		content_type: Option<&str>,
+		headers: &ObjectHeaders,
+		sse: &ObjectSse,
+	) -> Result<String> {
+		let builder = self
+			.client
+			.create_multipart_upload()
+			.bucket(&self.name)
+			.key(key)
+			.set_content_type(content_type.map(|ct| ct.to_string()));
+		let builder = headers.apply_to_create_multipart(builder);
+		let resp = sse.apply_to_create_multipart(builder).send().await?;
+
+		let upload_id = resp.upload_id().ok_or_else(|| Error::MultipartNoUploadId(key.to_string()))?;
+
+		Ok(upload_id.to_string())
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	async fn upload_file_part(
+		&self,
+		src_file: &Path,
+		key: &str,
+		upload_id: &str,
+		part_number: i32,
+		offset: u64,
+		length: u64,
+		sse: &ObjectSse,
+		progress: &FileProgress,
+	) -> Result<CompletedPart> {
+		let body = ByteStream::read_from()
+			.path(src_file)
+			.offset(offset)
+			.length(Length::Exact(length))
+			.build()
+			.await?;
+
+		self.upload_part(key, upload_id, part_number, progress.wrap_body(body), sse).await
+	}
+
+	async fn upload_part(&self, key: &str, upload_id: &str, part_number: i32, body: ByteStream, sse: &ObjectSse) -> Result<CompletedPart> {
+		let builder = self
+			.client
+			.upload_part()
+			.bucket(&self.name)
+			.key(key)
+			.upload_id(upload_id)
+			.part_number(part_number)
+			.body(body);
+		let resp = sse.apply_to_upload_part(builder).send().await?;
+
+		Ok(
+			CompletedPart::builder()
+				.set_e_tag(resp.e_tag().map(|t| t.to_string()))
+				.part_number(part_number)
+				.build(),
+		)
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	async fn upload_part_copy(
+		&self,
+		copy_source: &str,
+		key: &str,
+		upload_id: &str,
+		part_number: i32,
+		offset: u64,
+		length: u64,
+		sse: &ObjectSse,
+	) -> Result<CompletedPart> {
+		let builder = self
+			.client
+			.upload_part_copy()
+			.bucket(&self.name)
+			.key(key)
+			.upload_id(upload_id)
+			.part_number(part_number)
+			.copy_source(copy_source)
+			.copy_source_range(format!("bytes={offset}-{}", offset + length - 1));
+		let resp = sse.apply_to_upload_part_copy(builder).send().await?;
+
+		let e_tag = resp.copy_part_result().and_then(|r| r.e_tag()).map(|t| t.to_string());
+
+		Ok(CompletedPart::builder().set_e_tag(e_tag).part_number(part_number).build())
+	}
+
+	/// Complete the multipart upload if all parts succeeded, otherwise (or if the completion fails) abort it.
+	async fn complete_or_abort_multipart_upload(&self, key: &str, upload_id: &str, parts_res: Result<Vec<CompletedPart>>) -> Result<()> {
+		let res = match parts_res {
+			Ok(parts) => self.complete_multipart_upload(key, upload_id, parts).await,
+			Err(err) => Err(err),
+		};
+
+		// -- Abort on any failure
+		if let Err(err) = res {
+			// Note: The original error is the one that matters, so we just report an abort failure.
+			if let Err(abort_err) = self.abort_multipart_upload(key, upload_id).await {
+				eprintln!("Fail to abort multipart upload '{upload_id}' for key '{key}'. Cause: {abort_err}");
+			}
+			return Err(err);
+		}
+
+		Ok(())
+	}
+
+	async fn complete_multipart_upload(&self, key: &str, upload_id: &str, parts: Vec<CompletedPart>) -> Result<()> {
+		let completed_upload = CompletedMultipartUpload::builder().set_parts(Some(parts)).build();
+
+		self
+			.client
+			.complete_multipart_upload()
+			.bucket(&self.name)
+			.key(key)
+			.upload_id(upload_id)
+			.multipart_upload(completed_upload)
+			.send()
+			.await?;
+
+		Ok(())
+	}
+
+	async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+		self
+			.client
+			.abort_multipart_upload()
+			.bucket(&self.name)
+			.key(key)
+			.upload_id(upload_id)
+			.send()
+			.await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Multipart Steps
+
+// region:    --- Support
+
+/// Returns the `(part_number, offset, length)` of each part (S3 part numbers start at 1)
+fn part_ranges(size: u64, part_size: u64) -> impl Iterator<Item = (i32, u64, u64)> {
+	let part_count = size.div_ceil(part_size).max(1);
+	(0..part_count).map(move |part_idx| {
+		let offset = part_idx * part_size;
+		((part_idx + 1) as i32, offset, part_size.min(size - offset))
+	})
+}
+
+// endregion: --- Support
@@ -0,0 +1,174 @@
+use super::SBucket;
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::support::{CpOptions, file_matches_s3_etag};
+use crate::{Error, Result};
+use std::fs;
+use std::path::Path;
+
+/// "mv" Implementation (cp, then delete the transferred sources)
+///
+/// - Only the transferred sources are deleted (the skipped, excluded, and failed ones are kept).
+/// - Each source is deleted only once its destination is verified (size, and etag when comparable).
+/// - The returned report has the cp items followed by the deleted sources, and the transfer and delete failures.
+impl SBucket {
+	/// Move a local file or directory to this bucket (see `upload_path`).
+	pub async fn mv_upload(&self, src_path: &Path, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		let mut report = self.upload_path_report(src_path, prefix, opts.clone()).await?;
+
+		for (step, item) in mv_steps(&report, |action| matches!(action, CpAction::Upload { .. })) {
+			let res = match step {
+				MvStep::Delete => self.delete_uploaded_file(&item, &opts).await,
+				MvStep::DryRun { size } => Ok(dry_run_delete_src_item(&item, size)),
+			};
+			report.add(&item.dst, &item.src, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+
+	/// Move an object or the objects of a prefix of this bucket to a local file or directory (see `download_path`).
+	pub async fn mv_download(&self, base_key: &str, dst_path: &Path, opts: CpOptions) -> Result<CpReport> {
+		let mut report = self.download_path_report(base_key, dst_path, opts.clone()).await?;
+
+		for (step, item) in mv_steps(&report, |action| matches!(action, CpAction::Download)) {
+			let res = match step {
+				MvStep::Delete => self.delete_downloaded_object(&item, &opts).await,
+				MvStep::DryRun { size } => Ok(dry_run_delete_src_item(&item, size)),
+			};
+			report.add(&item.dst, &item.src, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+
+	/// Move an object or the objects of a prefix from the `src_bucket` to this bucket (see `copy_path`).
+	pub async fn mv_copy(&self, src_bucket: &SBucket, src_key: &str, prefix: &str, opts: CpOptions) -> Result<CpReport> {
+		let mut report = self.copy_path_report(src_bucket, src_key, prefix, opts.clone()).await?;
+
+		for (step, item) in mv_steps(&report, |action| matches!(action, CpAction::Copy | CpAction::Relay)) {
+			let res = match step {
+				MvStep::Delete => self.delete_copied_object(src_bucket, &item, &opts).await,
+				MvStep::DryRun { size } => Ok(dry_run_delete_src_item(&item, size)),
+			};
+			report.add(&item.dst, &item.src, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+}
+
+// region:    --- Verify & Delete
+
+impl SBucket {
+	/// Delete the local file of an upload item once the object (in this bucket) matches it.
+	async fn delete_uploaded_file(&self, item: &CpItem, opts: &CpOptions) -> Result<CpItem> {
+		let file = Path::new(&item.src);
+		let key = self.item_key(&item.dst)?;
+
+		let sitem = self.head_sitem_sse(key, &opts.sse).await?;
+		verify(item, sitem.size.max(0) as u64 == fs::metadata(file)?.len(), "size")?;
+		if let Some(etag) = sitem.etag.as_deref()
+			&& sitem.etag_is_md5 == Some(true)
+		{
+			verify(item, file_matches_s3_etag(file, etag, &opts.multipart), "etag")?;
+		}
+
+		fs::remove_file(file)?;
+		Ok(delete_src_item(item))
+	}
+
+	/// Delete the object (of this bucket) of a download item once the local file matches it.
+	async fn delete_downloaded_object(&self, item: &CpItem, opts: &CpOptions) -> Result<CpItem> {
+		let key = self.item_key(&item.src)?;
+		let file = Path::new(&item.dst);
+
+		// Note: The head also makes sure that the object did not change since the download (etag).
+		let sitem = self.head_sitem_sse(key, &opts.sse).await?;
+		verify(item, sitem.size.max(0) as u64 == fs::metadata(file)?.len(), "size")?;
+		if let Some(etag) = sitem.etag.as_deref()
+			&& sitem.etag_is_md5 == Some(true)
+		{
+			verify(item, file_matches_s3_etag(file, etag, &opts.multipart), "etag")?;
+		}
+
+		self.delete_object(key).await?;
+		Ok(delete_src_item(item))
+	}
+
+	/// Delete the object (of the `src_bucket`) of a copy item once the object (in this bucket) matches it.
+	/// Note: The etags are only compared when both are simple md5 etags (multipart etags depend on the part size of each side).
+	async fn delete_copied_object(&self, src_bucket: &SBucket, item: &CpItem, opts: &CpOptions) -> Result<CpItem> {
+		let src_key = src_bucket.item_key(&item.src)?;
+		let key = self.item_key(&item.dst)?;
+
+		let (src, dst) = (
+			src_bucket.head_sitem_sse(src_key, &opts.sse).await?,
+			self.head_sitem_sse(key, &opts.sse).await?,
+		);
+		verify(item, src.size == dst.size, "size")?;
+		if let (Some(src_etag), Some(dst_etag)) = (src.etag.as_deref(), dst.etag.as_deref())
+			&& !src_etag.contains('-')
+			&& !dst_etag.contains('-')
+			&& src.etag_is_md5 == Some(true)
+			&& dst.etag_is_md5 == Some(true)
+		{
+			verify(item, src_etag == dst_etag, "etag")?;
+		}
+
+		src_bucket.delete_object(src_key).await?;
+		Ok(delete_src_item(item))
+	}
+
+	fn item_key<'a>(&self, s3_url: &'a str) -> Result<&'a str> {
+		self.key_of_s3_url(s3_url).ok_or_else(|| Error::InvalidPath(s3_url.to_string()))
+	}
+}
+
+fn verify(item: &CpItem, ok: bool, cause: &'static str) -> Result<()> {
+	if ok {
+		Ok(())
+	} else {
+		Err(Error::MvNotVerified {
+			src: item.src.clone(),
+			dst: item.dst.clone(),
+			cause,
+		})
+	}
+}
+
+// endregion: --- Verify & Delete
+
+// region:    --- Support
+
+enum MvStep {
+	Delete,
+	DryRun { size: u64 },
+}
+
+/// The sources to delete, from the transferred items of the cp report (the other items are left as is).
+fn mv_steps(report: &CpReport, is_transfer: impl Fn(&CpAction) -> bool) -> Vec<(MvStep, CpItem)> {
+	report
+		.items
+		.iter()
+		.filter_map(|item| {
+			let step = match &item.action {
+				CpAction::DryRun { size, .. } => MvStep::DryRun { size: *size },
+				action if is_transfer(action) => MvStep::Delete,
+				_ => return None,
+			};
+			// Note: The action of the copy does not matter (only the src/dst are used)
+			Some((step, CpItem::new(CpAction::Ignore, &item.src, &item.dst)))
+		})
+		.collect()
+}
+
+/// The deleted source (the `dst` of a delete item is what is deleted)
+fn delete_src_item(item: &CpItem) -> CpItem {
+	CpItem::new(CpAction::Delete, &item.dst, &item.src)
+}
+
+fn dry_run_delete_src_item(item: &CpItem, size: u64) -> CpItem {
+	CpItem::new(CpAction::DryRun { verb: "delete", size }, &item.dst, &item.src)
+}
+
+// endregion: --- Support
@@ -0,0 +1,253 @@
+//! Transfer progress (per file and total).
+//! - When stdout is a terminal, rendered as live progress bars (bytes, throughput, ETA).
+//! - Otherwise, degraded to a plain text line every few seconds (so that logs do not get flooded).
+
+use aws_sdk_s3::primitives::{ByteStream, SdkBody};
+use http_body::{Body, Frame, SizeHint};
+use indicatif::{HumanBytes, HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressFinish, ProgressStyle};
+use std::io::IsTerminal;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Interval of the plain text progress lines (when stdout is not a terminal)
+const PLAIN_LINE_INTERVAL: Duration = Duration::from_secs(5);
+
+const FILE_BAR_TEMPLATE: &str = "[{bar:30}] {bytes:>10}/{total_bytes:10} {bytes_per_sec:>12}  ETA {eta:>3}  {wide_msg}";
+const TOTAL_BAR_TEMPLATE: &str = "[{bar:30}] {bytes:>10}/{total_bytes:10} {bytes_per_sec:>12}  ETA {eta:>3}  Total";
+
+// region:    --- Progress
+
+/// The progress of a cp/sync command (cheap to clone, all clones share the same total).
+/// Note: The default is hidden (no progress at all), e.g., for dry run or the library use.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+	inner: Option<Arc<ProgressInner>>,
+}
+
+#[derive(Debug)]
+struct ProgressInner {
+	mode: ProgressMode,
+	multi: MultiProgress,
+	/// Note: The total grows as the transfers start (skipped files are not part of it)
+	total: ProgressBar,
+	last_line: Mutex<Instant>,
+}
+
+#[derive(Debug)]
+enum ProgressMode {
+	Bars,
+	Lines,
+}
+
+impl Progress {
+	/// New progress, as bars if stdout is a terminal, otherwise as periodic plain text lines.
+	pub fn new() -> Self {
+		let (mode, draw_target) = if std::io::stdout().is_terminal() {
+			(ProgressMode::Bars, ProgressDrawTarget::stdout())
+		} else {
+			(ProgressMode::Lines, ProgressDrawTarget::hidden())
+		};
+
+		let multi = MultiProgress::with_draw_target(draw_target);
+		// Note: Cleared when dropped, so that a command failing before its end does not leave a bar behind.
+		let total = ProgressBar::new(0)
+			.with_style(bar_style(TOTAL_BAR_TEMPLATE))
+			.with_finish(ProgressFinish::AndClear);
+		let total = multi.add(total);
+
+		Progress {
+			inner: Some(Arc::new(ProgressInner {
+				mode,
+				multi,
+				total,
+				last_line: Mutex::new(Instant::now()),
+			})),
+		}
+	}
+
+	/// Start the progress of a file/object transfer of `size` bytes (added to the total).
+	pub fn start_file(&self, name: &str, size: u64) -> FileProgress {
+		let Some(inner) = &self.inner else {
+			return FileProgress::default();
+		};
+
+		inner.total.inc_length(size);
+		let bar = ProgressBar::new(size)
+			.with_style(bar_style(FILE_BAR_TEMPLATE))
+			.with_message(name.to_string());
+		let bar = inner.multi.insert_before(&inner.total, bar);
+
+		FileProgress {
+			inner: Some(Arc::new(FileBar {
+				bar,
+				progress: inner.clone(),
+			})),
+		}
+	}
+
+	/// Run `f` (typically a println) without the bars being drawn over it.
+	pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+		match self.inner.as_deref() {
+			Some(ProgressInner {
+				mode: ProgressMode::Bars,
+				multi,
+				..
+			}) => multi.suspend(f),
+			_ => f(),
+		}
+	}
+
+	/// Clear the total bar (at the end of the command).
+	pub fn finish(&self) {
+		if let Some(inner) = &self.inner {
+			inner.total.finish_and_clear();
+		}
+	}
+}
+
+impl ProgressInner {
+	/// Print the plain text line of the file (and the total) if the interval elapsed (lines mode only).
+	fn print_line_if_due(&self, bar: &ProgressBar) {
+		if !matches!(self.mode, ProgressMode::Lines) {
+			return;
+		}
+		let Ok(mut last_line) = self.last_line.lock() else {
+			return;
+		};
+		if last_line.elapsed() < PLAIN_LINE_INTERVAL {
+			return;
+		}
+		*last_line = Instant::now();
+
+		let total = &self.total;
+		println!(
+			"{:13} {}/{} {}   (total: {}/{}, {}/s, ETA {})",
+			"Progress",
+			HumanBytes(bar.position()),
+			HumanBytes(bar.length().unwrap_or_default()),
+			bar.message(),
+			HumanBytes(total.position()),
+			HumanBytes(total.length().unwrap_or_default()),
+			HumanBytes(total.per_sec() as u64),
+			HumanDuration(total.eta())
+		);
+	}
+}
+
+fn bar_style(template: &str) -> ProgressStyle {
+	ProgressStyle::with_template(template)
+		.unwrap_or_else(|_| ProgressStyle::default_bar())
+		.progress_chars("=> ")
+}
+
+// endregion: --- Progress
+
+// region:    --- FileProgress
+
+/// The progress of a single file/object transfer (the bar is cleared when the last clone is dropped).
+#[derive(Debug, Clone, Default)]
+pub struct FileProgress {
+	inner: Option<Arc<FileBar>>,
+}
+
+#[derive(Debug)]
+struct FileBar {
+	bar: ProgressBar,
+	progress: Arc<ProgressInner>,
+}
+
+impl Drop for FileBar {
+	fn drop(&mut self) {
+		self.bar.finish_and_clear();
+		self.progress.multi.remove(&self.bar);
+	}
+}
+
+impl FileProgress {
+	pub fn inc(&self, bytes: u64) {
+		if let Some(FileBar { bar, progress }) = self.inner.as_deref() {
+			bar.inc(bytes);
+			progress.total.inc(bytes);
+			progress.print_line_if_due(bar);
+		}
+	}
+
+	/// Add to the size of the transfer (e.g., stdin, for which the size is known only as it is read).
+	pub fn inc_length(&self, bytes: u64) {
+		if let Some(FileBar { bar, progress }) = self.inner.as_deref() {
+			bar.inc_length(bytes);
+			progress.total.inc_length(bytes);
+		}
+	}
+
+	/// Remove bytes already counted (e.g., when a request body is sent again on retry).
+	fn dec(&self, bytes: u64) {
+		if let Some(FileBar { bar, progress }) = self.inner.as_deref() {
+			bar.set_position(bar.position().saturating_sub(bytes));
+			progress.total.set_position(progress.total.position().saturating_sub(bytes));
+		}
+	}
+
+	/// Wrap a request body so that its bytes are counted as they are sent.
+	/// Note: The body stays retryable, and the bytes of a failed attempt are removed from the progress.
+	pub fn wrap_body(&self, body: ByteStream) -> ByteStream {
+		if self.inner.is_none() {
+			return body;
+		}
+
+		let body = body.into_inner();
+		let progress = self.clone();
+		let sent = Arc::new(AtomicU64::new(0));
+		ByteStream::new(SdkBody::retryable(move || {
+			progress.dec(sent.swap(0, Ordering::Relaxed));
+			let inner = body.try_clone().unwrap_or_else(SdkBody::taken);
+			SdkBody::from_body_1_x(ProgressBody {
+				inner,
+				progress: progress.clone(),
+				sent: sent.clone(),
+			})
+		}))
+	}
+}
+
+// endregion: --- FileProgress
+
+// region:    --- ProgressBody
+
+/// Request body counting the bytes of the data frames as they are polled (so, sent).
+struct ProgressBody {
+	inner: SdkBody,
+	progress: FileProgress,
+	sent: Arc<AtomicU64>,
+}
+
+impl Body for ProgressBody {
+	type Data = <SdkBody as Body>::Data;
+	type Error = <SdkBody as Body>::Error;
+
+	fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		let this = self.get_mut();
+		let res = Pin::new(&mut this.inner).poll_frame(cx);
+		if let Poll::Ready(Some(Ok(frame))) = &res
+			&& let Some(data) = frame.data_ref()
+		{
+			let len = data.len() as u64;
+			this.sent.fetch_add(len, Ordering::Relaxed);
+			this.progress.inc(len);
+		}
+		res
+	}
+
+	fn is_end_stream(&self) -> bool {
+		self.inner.is_end_stream()
+	}
+
+	fn size_hint(&self) -> SizeHint {
+		self.inner.size_hint()
+	}
+}
+
+// endregion: --- ProgressBody
@@ -0,0 +1,152 @@
+use super::SBucket;
+use crate::s3w::ObjectSse;
+use crate::s3w::get::get_object_error;
+use crate::s3w::progress::FileProgress;
+use crate::s3w::support::MultipartOptions;
+use crate::utils::md5::compute_md5;
+use crate::{Error, Result};
+use futures::{StreamExt, TryStreamExt};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// "ranged download" Implementation
+impl SBucket {
+	/// Download an object by byte ranges (up to `multipart.concurrency` ranges at a time).
+	///
+	/// The ranges are written at their offset in a pre-sized temporary file next to `dst_file`,
+	/// which is renamed to `dst_file` only once its size and etag have been verified.
+	///
+	/// - `etag_is_md5` - From the object head (e.g., false for SSE-KMS and SSE-C), the etag is verified only when true
+	#[allow(clippy::too_many_arguments)]
+	pub(super) async fn download_file_ranged(
+		&self,
+		key: &str,
+		dst_file: &Path,
+		size: u64,
+		etag: Option<&str>,
+		etag_is_md5: bool,
+		sse: &ObjectSse,
+		multipart: &MultipartOptions,
+		progress: &FileProgress,
+	) -> Result<()> {
+		let tmp_file = tmp_download_path(dst_file);
+
+		let res = self
+			.download_ranges_and_verify(key, &tmp_file, size, etag, etag_is_md5, sse, multipart, progress)
+			.await;
+
+		match res {
+			Ok(_) => {
+				fs::rename(&tmp_file, dst_file)?;
+				Ok(())
+			}
+			Err(err) => {
+				// Note: Best effort, the download error is the one to report.
+				let _ = fs::remove_file(&tmp_file);
+				Err(err)
+			}
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	async fn download_ranges_and_verify(
+		&self,
+		key: &str,
+		tmp_file: &Path,
+		size: u64,
+		etag: Option<&str>,
+		etag_is_md5: bool,
+		sse: &ObjectSse,
+		multipart: &MultipartOptions,
+		progress: &FileProgress,
+	) -> Result<()> {
+		// -- Pre-size the temporary file
+		File::create(tmp_file)?.set_len(size)?;
+
+		// -- Download the ranges
+		let part_size = multipart.part_size_for(size);
+		let part_count = size.div_ceil(part_size);
+		let actual_size: u64 = futures::stream::iter(0..part_count)
+			.map(|part_idx| {
+				let start = part_idx * part_size;
+				let end = (start + part_size).min(size) - 1; // inclusive
+				self.download_range(key, tmp_file, start, end, etag, sse, progress)
+			})
+			.buffer_unordered(multipart.concurrency)
+			.try_fold(0, |total, written| async move { Ok(total + written) })
+			.await?;
+
+		// -- Verify the size
+		// Note: The file was pre-sized, so this is the sum of the bytes written by the ranges.
+		if actual_size != size {
+			return Err(Error::DownloadSizeMismatch {
+				key: key.to_string(),
+				expected: size,
+				actual: actual_size,
+			});
+		}
+
+		// -- Verify the etag
+		// Note: Only simple (md5) etags can be verified here. Multipart etags depend on the upload part size.
+		if let Some(etag) = etag
+			&& !etag.contains('-')
+			&& etag_is_md5
+		{
+			let actual_etag = compute_md5(tmp_file)?;
+			if actual_etag != etag {
+				return Err(Error::DownloadEtagMismatch {
+					key: key.to_string(),
+					expected: etag.to_string(),
+					actual: actual_etag,
+				});
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Download the inclusive `start..=end` byte range at the same offset of the file, and returns the number of bytes written.
+	/// Note: When the etag is known, the request is conditional (if-match) so that an object changed during the download fails.
+	#[allow(clippy::too_many_arguments)]
+	async fn download_range(
+		&self,
+		key: &str,
+		file: &Path,
+		start: u64,
+		end: u64,
+		etag: Option<&str>,
+		sse: &ObjectSse,
+		progress: &FileProgress,
+	) -> Result<u64> {
+		let builder = self
+			.client
+			.get_object()
+			.bucket(&self.name)
+			.key(key)
+			.range(format!("bytes={start}-{end}"))
+			.set_if_match(etag.map(|etag| format!("\"{etag}\"")));
+		let resp = sse.apply_to_get(builder).send().await.map_err(|err| get_object_error(key, err))?;
+
+		let mut file = OpenOptions::new().write(true).open(file)?;
+		file.seek(SeekFrom::Start(start))?;
+		let mut buf_writer = BufWriter::new(file);
+
+		let mut written: u64 = 0;
+		let mut data = resp.body;
+		while let Some(bytes) = data.try_next().await? {
+			buf_writer.write_all(&bytes)?;
+			written += bytes.len() as u64;
+			progress.inc(bytes.len() as u64);
+		}
+		buf_writer.flush()?;
+
+		Ok(written)
+	}
+}
+
+/// The temporary file path of a ranged download (same directory, so that the final rename is atomic)
+fn tmp_download_path(dst_file: &Path) -> PathBuf {
+	let file_name = dst_file.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+	dst_file.with_file_name(format!("{file_name}.ss3-download"))
+}
@@ -0,0 +1,116 @@
+use crate::Result;
+use crate::output::{Event, EventStatus, emit, is_text_output};
+use crate::s3w::SBucket;
+use aws_sdk_s3::types::ObjectIdentifier;
+
+/// The content of a bucket, to be removed before deleting the bucket (`rb --force`)
+#[derive(Debug, Default)]
+pub struct BucketContent {
+	/// All the object versions (for a non versioned bucket, the objects with the "null" version)
+	pub versions: Vec<ObjectIdentifier>,
+	pub delete_markers: Vec<ObjectIdentifier>,
+	/// The in progress multipart uploads as (key, upload_id)
+	pub uploads: Vec<(String, String)>,
+}
+
+impl BucketContent {
+	pub fn is_empty(&self) -> bool {
+		self.versions.is_empty() && self.delete_markers.is_empty() && self.uploads.is_empty()
+	}
+}
+
+impl SBucket {
+	/// List all the content of the bucket (all pages), object versions, delete markers, and in progress multipart uploads.
+	pub async fn list_bucket_content(&self) -> Result<BucketContent> {
+		let mut content = BucketContent::default();
+
+		// -- The object versions and delete markers
+		let (mut key_marker, mut version_id_marker) = (None, None);
+		loop {
+			let resp = self
+				.client
+				.list_object_versions()
+				.bucket(&self.name)
+				.set_key_marker(key_marker)
+				.set_version_id_marker(version_id_marker)
+				.send()
+				.await?;
+
+			for version in resp.versions() {
+				content.versions.push(object_id(version.key(), version.version_id())?);
+			}
+			for marker in resp.delete_markers() {
+				content.delete_markers.push(object_id(marker.key(), marker.version_id())?);
+			}
+
+			if resp.is_truncated() != Some(true) {
+				break;
+			}
+			key_marker = resp.next_key_marker().map(String::from);
+			version_id_marker = resp.next_version_id_marker().map(String::from);
+		}
+
+		// -- The in progress multipart uploads
+		let (mut key_marker, mut upload_id_marker) = (None, None);
+		loop {
+			let resp = self
+				.client
+				.list_multipart_uploads()
+				.bucket(&self.name)
+				.set_key_marker(key_marker)
+				.set_upload_id_marker(upload_id_marker)
+				.send()
+				.await?;
+
+			for upload in resp.uploads() {
+				if let (Some(key), Some(upload_id)) = (upload.key(), upload.upload_id()) {
+					content.uploads.push((key.to_string(), upload_id.to_string()));
+				}
+			}
+
+			if resp.is_truncated() != Some(true) {
+				break;
+			}
+			key_marker = resp.next_key_marker().map(String::from);
+			upload_id_marker = resp.next_upload_id_marker().map(String::from);
+		}
+
+		Ok(content)
+	}
+
+	/// Remove all the content of the bucket (see `list_bucket_content`), so that the bucket can be deleted.
+	pub async fn empty_bucket(&self, content: BucketContent) -> Result<()> {
+		let BucketContent {
+			versions,
+			delete_markers,
+			uploads,
+		} = content;
+
+		for (key, upload_id) in uploads {
+			self
+				.client
+				.abort_multipart_upload()
+				.bucket(&self.name)
+				.key(&key)
+				.upload_id(&upload_id)
+				.send()
+				.await?;
+			if is_text_output() {
+				println!("{:13} {}   (upload: {upload_id})", "Aborted", self.s3_url(&key));
+			} else {
+				emit(Event::new("abort_upload", self.s3_url(&key), EventStatus::Ok).with_reason(upload_id));
+			}
+		}
+
+		self.delete_object_ids(versions.into_iter().chain(delete_markers).collect()).await
+	}
+}
+
+fn object_id(key: Option<&str>, version_id: Option<&str>) -> Result<ObjectIdentifier> {
+	let object_id = ObjectIdentifier::builder()
+		.key(key.unwrap_or_default())
+		.set_version_id(version_id.map(String::from))
+		.build()?;
+
+	Ok(object_id)
+}
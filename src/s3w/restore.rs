@@ -0,0 +1,257 @@
+//! Restore of the archived objects (GLACIER, DEEP_ARCHIVE), so that they can be downloaded for a number of days.
+
+use crate::output::{Event, EventStatus, emit, is_text_output};
+use crate::s3w::{ListOptions, SBucket, SItem};
+use crate::{Error, Result};
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use aws_sdk_s3::types::{GlacierJobParameters, RestoreRequest, Tier};
+use globset::GlobSet;
+use std::fmt;
+use std::time::Duration;
+
+/// Default number of days the restored copies are kept
+pub const DEFAULT_RESTORE_DAYS: i32 = 1;
+
+/// Interval of the restore status checks of `--wait` (restores take minutes to hours)
+const RESTORE_WAIT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The S3 error code of a restore request on an object already being restored
+const RESTORE_ALREADY_IN_PROGRESS: &str = "RestoreAlreadyInProgress";
+
+// region:    --- RestoreOptions
+
+#[derive(Clone)]
+pub struct RestoreOptions {
+	pub recursive: bool,
+	pub excludes: Option<GlobSet>,
+	pub includes: Option<GlobSet>,
+	/// Number of days the restored copies are kept
+	pub days: i32,
+	pub tier: Tier,
+	/// Wait (polling) until the objects are restored
+	pub wait: bool,
+	/// Only report the objects that would be restored
+	pub dry_run: bool,
+}
+
+impl Default for RestoreOptions {
+	fn default() -> Self {
+		RestoreOptions {
+			recursive: false,
+			excludes: None,
+			includes: None,
+			days: DEFAULT_RESTORE_DAYS,
+			tier: Tier::Standard,
+			wait: false,
+			dry_run: false,
+		}
+	}
+}
+
+// endregion: --- RestoreOptions
+
+// region:    --- RestoreStatus
+
+/// The restore status of an archived object (from the `x-amz-restore` header of the head)
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreStatus {
+	InProgress,
+	Restored { expiry: Option<DateTime> },
+}
+
+impl RestoreStatus {
+	/// Parse the `x-amz-restore` header value
+	/// e.g., `ongoing-request="true"`, or `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`
+	pub fn from_header(val: &str) -> Option<RestoreStatus> {
+		let value_of = |name: &str| {
+			let start = val.find(&format!("{name}=\""))? + name.len() + 2;
+			let len = val[start..].find('"')?;
+			Some(&val[start..start + len])
+		};
+
+		match value_of("ongoing-request")? {
+			"true" => Some(RestoreStatus::InProgress),
+			"false" => Some(RestoreStatus::Restored {
+				expiry: value_of("expiry-date").and_then(|date| DateTime::from_str(date, DateTimeFormat::HttpDate).ok()),
+			}),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for RestoreStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RestoreStatus::InProgress => write!(f, "restoring"),
+			RestoreStatus::Restored { expiry: None } => write!(f, "restored"),
+			RestoreStatus::Restored { expiry: Some(expiry) } => {
+				let expiry = expiry.fmt(DateTimeFormat::DateTime).unwrap_or_default();
+				write!(f, "restored until {expiry}")
+			}
+		}
+	}
+}
+
+// endregion: --- RestoreStatus
+
+// region:    --- Restore
+
+impl SBucket {
+	/// List the objects to be restored, the object of the key, or all the objects of a prefix with `opts.recursive`
+	/// (matching the includes/excludes).
+	/// Note: Not archived objects are listed as well (reported as skipped by `restore_objects`).
+	pub async fn list_to_restore(&self, key: &str, opts: &RestoreOptions) -> Result<Vec<SItem>> {
+		if !opts.recursive {
+			return Ok(vec![self.head_sitem(key).await?]);
+		}
+
+		let list_options = ListOptions {
+			recursive: true,
+			includes: opts.includes.clone(),
+			excludes: opts.excludes.clone(),
+			..Default::default()
+		};
+
+		self.list_all(key, list_options).await
+	}
+
+	/// Request the restore of the archived objects (the others are skipped), and returns the keys being restored.
+	///
+	/// Note: The per key errors do not stop the next restores.
+	///       They are returned at the end as a `RestoreFailed` error.
+	pub async fn restore_objects(&self, sitems: &[SItem], opts: &RestoreOptions) -> Result<Vec<String>> {
+		let mut restoring: Vec<String> = Vec::new();
+		let mut failures: Vec<String> = Vec::new();
+		let options = format!("days: {}, tier: {}", opts.days, opts.tier.as_str());
+
+		for sitem in sitems {
+			let s3_url = self.s3_url(&sitem.key);
+
+			if !sitem.is_archived() {
+				if is_text_output() {
+					println!("{:13} {s3_url}   (not archived)", "Skip");
+				} else {
+					emit(Event::new("restore", s3_url, EventStatus::Skipped).with_reason("not archived"));
+				}
+				continue;
+			}
+
+			if opts.dry_run {
+				if is_text_output() {
+					println!("{:13} {s3_url}   ({options})", "Would restore");
+				} else {
+					emit(Event::new("restore", s3_url, EventStatus::DryRun));
+				}
+				continue;
+			}
+
+			match self.restore_object(&sitem.key, opts).await {
+				Ok(already_in_progress) => {
+					let note = if already_in_progress { ", already in progress" } else { "" };
+					if is_text_output() {
+						println!("{:13} {s3_url}   ({options}{note})", "Restoring");
+					} else {
+						let event = Event::new("restore", s3_url, EventStatus::Ok);
+						let event = if already_in_progress {
+							event.with_reason("already in progress")
+						} else {
+							event
+						};
+						emit(event);
+					}
+					restoring.push(sitem.key.clone());
+				}
+				Err(err) => {
+					if is_text_output() {
+						eprintln!("{:13} {s3_url}", "FAILED");
+					} else {
+						emit(Event::new("restore", &s3_url, EventStatus::Failed).with_error(&err));
+					}
+					failures.push(format!("{s3_url}\n    Cause: {err}"));
+				}
+			}
+		}
+
+		if failures.is_empty() {
+			Ok(restoring)
+		} else {
+			Err(Error::RestoreFailed {
+				total: sitems.len(),
+				failures,
+			})
+		}
+	}
+
+	/// Wait until the objects of the keys are restored (checking their restore status every minute).
+	pub async fn wait_restored(&self, keys: Vec<String>) -> Result<()> {
+		let mut pending = keys;
+
+		loop {
+			let mut still_pending = Vec::new();
+			for key in pending {
+				match self.head_sitem(&key).await?.restore {
+					Some(RestoreStatus::InProgress) => still_pending.push(key),
+					status => {
+						let s3_url = self.s3_url(&key);
+						if is_text_output() {
+							let status = status
+								.map(|status| status.to_string())
+								.unwrap_or_else(|| "not archived".to_string());
+							println!("{:13} {s3_url}   ({status})", "Restored");
+						} else {
+							emit(Event::new("restored", s3_url, EventStatus::Ok));
+						}
+					}
+				}
+			}
+
+			if still_pending.is_empty() {
+				return Ok(());
+			}
+
+			if is_text_output() {
+				println!(
+					"{:13} {} objects still restoring (next check in {}s)",
+					"Waiting",
+					still_pending.len(),
+					RESTORE_WAIT_INTERVAL.as_secs()
+				);
+			}
+			pending = still_pending;
+			tokio::time::sleep(RESTORE_WAIT_INTERVAL).await;
+		}
+	}
+
+	/// Returns true if the restore was already in progress.
+	async fn restore_object(&self, key: &str, opts: &RestoreOptions) -> Result<bool> {
+		let restore_request = RestoreRequest::builder()
+			.days(opts.days)
+			.glacier_job_parameters(GlacierJobParameters::builder().tier(opts.tier.clone()).build()?)
+			.build();
+
+		let res = self
+			.client
+			.restore_object()
+			.bucket(&self.name)
+			.key(key)
+			.restore_request(restore_request)
+			.send()
+			.await;
+
+		match res.map_err(Error::from) {
+			Ok(_) => Ok(false),
+			Err(Error::AwsSdkErrorWrapper { code, .. }) if code == RESTORE_ALREADY_IN_PROGRESS => Ok(true),
+			Err(err) => Err(err),
+		}
+	}
+}
+
+// endregion: --- Restore
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-restore.rs"]
+mod tests;
+
+// endregion: --- Tests
@@ -1,5 +1,22 @@
-use crate::Result;
-use crate::s3w::SBucket;
+use crate::output::{Event, EventStatus, emit, is_text_output};
+use crate::s3w::support::folder_prefix;
+use crate::s3w::{ListOptions, SBucket, SItem};
+use crate::{Error, Result};
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use globset::GlobSet;
+
+/// Max number of keys of a DeleteObjects request (S3 limit)
+const DELETE_OBJECTS_MAX_KEYS: usize = 1000;
+
+#[derive(Default, Clone)]
+pub struct RmOptions {
+	pub recursive: bool,
+	pub excludes: Option<GlobSet>,
+	pub includes: Option<GlobSet>,
+	pub force: bool,
+	/// Only report the objects that would be deleted
+	pub dry_run: bool,
+}
 
 impl SBucket {
 	pub async fn delete_object(&self, key: &str) -> Result<()> {
@@ -9,4 +26,77 @@ impl SBucket {
 
 		Ok(())
 	}
+
+	/// List the objects (all pages) of a prefix to be deleted by a recursive rm (matching the includes/excludes).
+	///
+	/// Note: The prefix is taken as a "folder" (trailing '/' added), so that `logs` does not also delete `logs-old/...` or `logs.txt`.
+	pub async fn list_to_rm(&self, prefix: &str, opts: &RmOptions) -> Result<Vec<SItem>> {
+		let prefix = folder_prefix(prefix);
+
+		let list_options = ListOptions {
+			recursive: true,
+			includes: opts.includes.clone(),
+			excludes: opts.excludes.clone(),
+			..Default::default()
+		};
+
+		self.list_all(&prefix, list_options).await
+	}
+
+	/// Delete the keys by batches of up to 1000 (DeleteObjects), printing each deleted key.
+	///
+	/// Note: The per key errors of a batch do not stop the next batches.
+	///       They are returned at the end as a `DeleteObjectsFailed` error.
+	pub async fn delete_objects(&self, keys: &[&str]) -> Result<()> {
+		let object_ids = keys
+			.iter()
+			.map(|key| ObjectIdentifier::builder().key(*key).build())
+			.collect::<core::result::Result<Vec<_>, _>>()?;
+
+		self.delete_object_ids(object_ids).await
+	}
+
+	/// Same as `delete_objects`, but for object ids (e.g., with a version id).
+	pub(super) async fn delete_object_ids(&self, object_ids: Vec<ObjectIdentifier>) -> Result<()> {
+		let total = object_ids.len();
+		let mut failures: Vec<String> = Vec::new();
+
+		for batch in object_ids.chunks(DELETE_OBJECTS_MAX_KEYS) {
+			let delete = Delete::builder().set_objects(Some(batch.to_vec())).quiet(false).build()?;
+
+			let resp = self.client.delete_objects().bucket(&self.name).delete(delete).send().await?;
+
+			for deleted in resp.deleted() {
+				let s3_url = self.s3_url_with_version(deleted.key().unwrap_or_default(), deleted.version_id());
+				if is_text_output() {
+					println!("{:13} {s3_url}", "Deleted");
+				} else {
+					emit(Event::new("delete", s3_url, EventStatus::Ok));
+				}
+			}
+			for err in resp.errors() {
+				let s3_url = self.s3_url_with_version(err.key().unwrap_or_default(), err.version_id());
+				let cause = format!("{} - {}", err.code().unwrap_or("NO_CODE"), err.message().unwrap_or_default());
+				if is_text_output() {
+					eprintln!("{:13} {s3_url}", "FAILED");
+				} else {
+					emit(Event::new("delete", &s3_url, EventStatus::Failed).with_error(&cause));
+				}
+				failures.push(format!("{s3_url}\n    Cause: {cause}"));
+			}
+		}
+
+		if failures.is_empty() {
+			Ok(())
+		} else {
+			Err(Error::DeleteObjectsFailed { total, failures })
+		}
+	}
+
+	fn s3_url_with_version(&self, key: &str, version_id: Option<&str>) -> String {
+		match version_id {
+			Some(version_id) => format!("{}   (version: {version_id})", self.s3_url(key)),
+			None => self.s3_url(key),
+		}
+	}
 }
@@ -1,35 +1,60 @@
+use crate::s3w::ObjectSse;
+use crate::s3w::cred::CredIdentity;
 use aws_sdk_s3::Client;
 use std::collections::HashSet;
 
 pub struct SBucketConfig {
 	pub default_ignore_upload_names: Option<HashSet<String>>,
+	pub cred_identity: Option<CredIdentity>,
 }
 
 pub struct SBucket {
 	pub client: Client,
 	pub name: String,
 	pub default_ignore_upload_names: Option<HashSet<String>>,
+	/// The identity of the credential of the client (None when built from a client directly)
+	pub cred_identity: Option<CredIdentity>,
 }
 
 impl SBucket {
 	/// Constructor
 	pub fn from_client_and_name(client: Client, name: String, config: Option<SBucketConfig>) -> SBucket {
+		let (default_ignore_upload_names, cred_identity) = match config {
+			Some(config) => (config.default_ignore_upload_names, config.cred_identity),
+			None => (None, None),
+		};
 		SBucket {
 			client,
 			name,
-			default_ignore_upload_names: config.and_then(|d| d.default_ignore_upload_names),
+			default_ignore_upload_names,
+			cred_identity,
 		}
 	}
 }
 
 impl SBucket {
-	pub async fn exists(&self, key: &str) -> bool {
+	/// Note: The SSE-C key is needed for the head of the SSE-C objects (otherwise, a bad request error).
+	pub async fn exists(&self, key: &str, sse: &ObjectSse) -> bool {
 		let builder = self.client.head_object().key(key).bucket(&self.name);
-		let resp = builder.send().await;
+		let resp = sse.apply_to_head(builder).send().await;
 		resp.is_ok()
 	}
 
 	pub fn s3_url(&self, key: &str) -> String {
 		format!("s3://{}/{key}", self.name)
 	}
+
+	/// The key of a `s3_url` of this bucket (None if the url is not from this bucket)
+	pub(super) fn key_of_s3_url<'a>(&self, s3_url: &'a str) -> Option<&'a str> {
+		s3_url.strip_prefix("s3://")?.strip_prefix(self.name.as_str())?.strip_prefix('/')
+	}
+
+	/// Returns true if the other bucket is reachable with the same credential/endpoint (i.e., a server side copy is possible).
+	/// Note: When one of the identities is unknown, assume the same.
+	pub fn has_same_cred(&self, other: &SBucket) -> bool {
+		match (&self.cred_identity, &other.cred_identity) {
+			(Some(identity), Some(other_identity)) => identity == other_identity,
+			_ => true,
+		}
+	}
 }
@@ -1,19 +1,31 @@
+use crate::s3w::RestoreStatus;
+use crate::s3w::sse::head_etag_is_md5;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::primitives::DateTime;
 use aws_sdk_s3::types::{CommonPrefix, Object};
 
 // region:    --- S3Item
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SItemType {
 	Object,
 	Prefix,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SItem {
-	#[allow(unused)]
 	pub typ: SItemType,
 	pub key: String,
 	pub etag: Option<String>,
 	pub size: i64,
+	pub last_modified: Option<DateTime>,
+	/// e.g., "STANDARD", "GLACIER" (None for the prefixes)
+	pub storage_class: Option<String>,
+	/// The owner display name, or id (only when listed with `fetch_owner`)
+	pub owner: Option<String>,
+	/// The restore status of the archived objects (only from the head)
+	pub restore: Option<RestoreStatus>,
+	/// If the etag is the md5 of the content (not for SSE-KMS and SSE-C), only from the head (the listing does not tell the encryption)
+	pub etag_is_md5: Option<bool>,
 }
 
 /// Constructors
@@ -21,14 +33,33 @@ impl SItem {
 	pub fn from_object(obj: &Object) -> SItem {
 		let key = obj.key().unwrap_or_default().to_string();
 		let size = obj.size();
-		let etag = obj
-			.e_tag()
-			.map(|etag| etag.strip_prefix('"').unwrap_or(etag).strip_suffix('"').unwrap_or(etag).to_string());
+		let etag = obj.e_tag().map(clean_etag);
+		let owner = obj.owner().and_then(|o| o.display_name().or(o.id())).map(String::from);
 		SItem {
 			key,
 			typ: SItemType::Object,
 			size: size.unwrap_or(-1),
 			etag,
+			last_modified: obj.last_modified().copied(),
+			storage_class: obj.storage_class().map(|sc| sc.as_str().to_string()),
+			owner,
+			restore: None,
+			etag_is_md5: None,
+		}
+	}
+
+	pub fn from_head(key: &str, head: &HeadObjectOutput) -> SItem {
+		SItem {
+			key: key.to_string(),
+			typ: SItemType::Object,
+			size: head.content_length().unwrap_or(-1),
+			etag: head.e_tag().map(clean_etag),
+			last_modified: head.last_modified().copied(),
+			// Note: The head has no storage class for the standard one
+			storage_class: Some(head.storage_class().map(|sc| sc.as_str()).unwrap_or("STANDARD").to_string()),
+			owner: None,
+			restore: head.restore().and_then(RestoreStatus::from_header),
+			etag_is_md5: Some(head_etag_is_md5(head)),
 		}
 	}
 
@@ -39,6 +70,11 @@ impl SItem {
 			typ: SItemType::Prefix,
 			size: 0,
 			etag: None,
+			last_modified: None,
+			storage_class: None,
+			owner: None,
+			restore: None,
+			etag_is_md5: None,
 		}
 	}
 
@@ -48,8 +84,29 @@ impl SItem {
 			typ: SItemType::Prefix,
 			size: 0,
 			etag: None,
+			last_modified: None,
+			storage_class: None,
+			owner: None,
+			restore: None,
+			etag_is_md5: None,
 		}
 	}
 }
 
+impl SItem {
+	/// True for the objects which must be restored before being downloaded (GLACIER, DEEP_ARCHIVE)
+	pub fn is_archived(&self) -> bool {
+		matches!(self.storage_class.as_deref(), Some("GLACIER" | "DEEP_ARCHIVE"))
+	}
+}
+
 // endregion: --- S3Item
+
+// region:    --- Support
+
+/// Remove the eventual surrounding double quotes of the S3 etag
+fn clean_etag(etag: &str) -> String {
+	etag.strip_prefix('"').unwrap_or(etag).strip_suffix('"').unwrap_or(etag).to_string()
+}
+
+// endregion: --- Support
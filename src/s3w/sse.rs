@@ -0,0 +1,213 @@
+//! The server side encryption of the uploaded/copied objects (`--sse AES256|aws:kms`, `--sse-kms-key-id`, `--sse-c-key`).
+//!
+//! - SSE-S3 and SSE-KMS are only set on the requests writing the objects (S3 decrypts them transparently).
+//! - SSE-C (customer key) must be given on every request on the objects (put, multipart, copy, head, get).
+//!   For the copies, the source objects are expected to be encrypted with the same key.
+//!
+//! Note: The etags of the SSE-KMS and SSE-C objects are not the md5 of their content,
+//!       so they are not compared/verified with the files (e.g., `--over etag` transfers them again).
+
+use crate::s3w::support::read_256_bit_key_file;
+use crate::{Error, Result};
+use aws_sdk_s3::operation::copy_object::builders::CopyObjectFluentBuilder;
+use aws_sdk_s3::operation::create_multipart_upload::builders::CreateMultipartUploadFluentBuilder;
+use aws_sdk_s3::operation::get_object::builders::GetObjectFluentBuilder;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::operation::head_object::builders::HeadObjectFluentBuilder;
+use aws_sdk_s3::operation::put_object::builders::PutObjectFluentBuilder;
+use aws_sdk_s3::operation::upload_part::builders::UploadPartFluentBuilder;
+use aws_sdk_s3::operation::upload_part_copy::builders::UploadPartCopyFluentBuilder;
+use aws_sdk_s3::types::ServerSideEncryption;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::fmt;
+use std::path::Path;
+
+/// The only SSE-C algorithm supported by S3
+const SSE_C_ALGORITHM: &str = "AES256";
+
+/// The SSE-C key size (256 bits)
+const SSE_C_KEY_LEN: usize = 32;
+
+// region:    --- ObjectSse
+
+#[derive(Debug, Default, Clone)]
+pub struct ObjectSse {
+	/// SSE-S3 (`AES256`) or SSE-KMS (`aws:kms`)
+	pub encryption: Option<ServerSideEncryption>,
+	/// The KMS key of SSE-KMS (the AWS managed one when none)
+	pub kms_key_id: Option<String>,
+	/// SSE-C
+	pub customer_key: Option<SseCustomerKey>,
+}
+
+impl ObjectSse {
+	/// Build and validate the encryption options.
+	/// Note: A KMS key id without encryption implies `aws:kms`.
+	pub fn new(encryption: Option<&str>, kms_key_id: Option<String>, customer_key: Option<SseCustomerKey>) -> Result<Self> {
+		let encryption = match (encryption.map(ServerSideEncryption::from), &kms_key_id) {
+			(None, Some(_)) => Some(ServerSideEncryption::AwsKms),
+			(Some(encryption), Some(_)) if encryption != ServerSideEncryption::AwsKms => {
+				return Err(Error::CmdInvalid("'--sse-kms-key-id' requires '--sse aws:kms'."));
+			}
+			(encryption, _) => encryption,
+		};
+
+		if encryption.is_some() && customer_key.is_some() {
+			return Err(Error::CmdInvalid(
+				"'--sse-c-key' cannot be used with '--sse' or '--sse-kms-key-id'.",
+			));
+		}
+
+		Ok(ObjectSse {
+			encryption,
+			kms_key_id,
+			customer_key,
+		})
+	}
+
+	fn customer_algorithm(&self) -> Option<String> {
+		self.customer_key.as_ref().map(|_| SSE_C_ALGORITHM.to_string())
+	}
+
+	fn customer_key_b64(&self) -> Option<String> {
+		self.customer_key.as_ref().map(|key| key.key_b64.clone())
+	}
+
+	fn customer_key_md5_b64(&self) -> Option<String> {
+		self.customer_key.as_ref().map(|key| key.key_md5_b64.clone())
+	}
+}
+
+/// Implement the `apply_to_...` of the request builders (the same setters on each of them)
+/// - `encryption` - The requests writing the objects (the encryption, and the customer key)
+/// - `customer_key` - The other requests on the objects (only the customer key)
+macro_rules! impl_apply_object_sse {
+	(
+		encryption: [$($enc_fn_name:ident: $enc_builder:ty),* $(,)?],
+		customer_key: [$($key_fn_name:ident: $key_builder:ty),* $(,)?] $(,)?
+	) => {
+		impl ObjectSse {
+			$(
+				pub(super) fn $enc_fn_name(&self, builder: $enc_builder) -> $enc_builder {
+					builder
+						.set_server_side_encryption(self.encryption.clone())
+						.set_ssekms_key_id(self.kms_key_id.clone())
+						.set_sse_customer_algorithm(self.customer_algorithm())
+						.set_sse_customer_key(self.customer_key_b64())
+						.set_sse_customer_key_md5(self.customer_key_md5_b64())
+				}
+			)*
+			$(
+				pub(super) fn $key_fn_name(&self, builder: $key_builder) -> $key_builder {
+					builder
+						.set_sse_customer_algorithm(self.customer_algorithm())
+						.set_sse_customer_key(self.customer_key_b64())
+						.set_sse_customer_key_md5(self.customer_key_md5_b64())
+				}
+			)*
+		}
+	};
+}
+
+impl_apply_object_sse!(
+	encryption: [
+		apply_to_put: PutObjectFluentBuilder,
+		apply_to_create_multipart: CreateMultipartUploadFluentBuilder,
+	],
+	customer_key: [
+		apply_to_head: HeadObjectFluentBuilder,
+		apply_to_get: GetObjectFluentBuilder,
+		apply_to_upload_part: UploadPartFluentBuilder,
+	],
+);
+
+/// The copies (the customer key is also the one of the source object)
+impl ObjectSse {
+	pub(super) fn apply_to_copy(&self, builder: CopyObjectFluentBuilder) -> CopyObjectFluentBuilder {
+		builder
+			.set_server_side_encryption(self.encryption.clone())
+			.set_ssekms_key_id(self.kms_key_id.clone())
+			.set_sse_customer_algorithm(self.customer_algorithm())
+			.set_sse_customer_key(self.customer_key_b64())
+			.set_sse_customer_key_md5(self.customer_key_md5_b64())
+			.set_copy_source_sse_customer_algorithm(self.customer_algorithm())
+			.set_copy_source_sse_customer_key(self.customer_key_b64())
+			.set_copy_source_sse_customer_key_md5(self.customer_key_md5_b64())
+	}
+
+	pub(super) fn apply_to_upload_part_copy(&self, builder: UploadPartCopyFluentBuilder) -> UploadPartCopyFluentBuilder {
+		builder
+			.set_sse_customer_algorithm(self.customer_algorithm())
+			.set_sse_customer_key(self.customer_key_b64())
+			.set_sse_customer_key_md5(self.customer_key_md5_b64())
+			.set_copy_source_sse_customer_algorithm(self.customer_algorithm())
+			.set_copy_source_sse_customer_key(self.customer_key_b64())
+			.set_copy_source_sse_customer_key_md5(self.customer_key_md5_b64())
+	}
+}
+
+// endregion: --- ObjectSse
+
+/// Returns true if the etag of the object is the md5 of its content, from its head (e.g., not for SSE-KMS and SSE-C).
+/// Note: Decided by the object encryption (e.g., a bucket default SSE-KMS), not by the encryption options of the command.
+pub(super) fn head_etag_is_md5(head: &HeadObjectOutput) -> bool {
+	head.sse_customer_algorithm().is_none()
+		&& !matches!(
+			head.server_side_encryption(),
+			Some(ServerSideEncryption::AwsKms | ServerSideEncryption::AwsKmsDsse)
+		)
+}
+
+// region:    --- SseCustomerKey
+
+/// The 256-bit key of SSE-C (base64 encoded, with the base64 of its md5, as sent to S3)
+#[derive(Clone)]
+pub struct SseCustomerKey {
+	key_b64: String,
+	key_md5_b64: String,
+}
+
+// implement Debug but redact the key
+impl fmt::Debug for SseCustomerKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "SseCustomerKey {{ key: REDACTED, key_md5: {} }}", self.key_md5_b64)
+	}
+}
+
+impl SseCustomerKey {
+	/// The key of `--sse-c-key`, either base64 encoded, or a file path (of the raw 32 bytes, or of the base64 encoded key).
+	/// Note: The errors never contain the key material.
+	pub fn from_arg(val: &str) -> Result<Self> {
+		let path = Path::new(val);
+		if path.is_file() {
+			return Self::from_key(&read_256_bit_key_file(path)?);
+		}
+
+		let key = BASE64
+			.decode(val.trim())
+			.map_err(|_| Error::InvalidSseCustomerKey("not a valid base64 key, nor a key file"))?;
+		Self::from_key(&key)
+	}
+
+	fn from_key(key: &[u8]) -> Result<Self> {
+		if key.len() != SSE_C_KEY_LEN {
+			return Err(Error::InvalidSseCustomerKey("the key must be 256 bits (32 bytes)"));
+		}
+
+		Ok(SseCustomerKey {
+			key_b64: BASE64.encode(key),
+			key_md5_b64: BASE64.encode(md5::compute(key).0),
+		})
+	}
+}
+
+// endregion: --- SseCustomerKey
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-sse.rs"]
+mod tests;
+
+// endregion: --- Tests
@@ -1,10 +1,25 @@
 use super::SBucket;
-use crate::{Error, Result};
+use crate::{
+	DEFAULT_MULTIPART_PART_SIZE, DEFAULT_MULTIPART_THRESHOLD, DEFAULT_PART_CONCURRENCY, Error, Result, S3_MAX_PART_SIZE, S3_MAX_PARTS,
+	S3_MIN_PART_SIZE,
+};
+use aws_sdk_s3::operation::copy_object::builders::CopyObjectFluentBuilder;
+use aws_sdk_s3::operation::create_multipart_upload::builders::CreateMultipartUploadFluentBuilder;
+use aws_sdk_s3::operation::get_object::GetObjectOutput;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::operation::put_object::builders::PutObjectFluentBuilder;
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use aws_sdk_s3::types::StorageClass;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use globset::GlobSet;
+use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
+use std::time::SystemTime;
 // use tokio_stream::StreamExt;
-use crate::s3w::SItemsCache;
-use crate::utils::md5::compute_md5;
+use crate::s3w::{CompressMode, EncryptKey, HeaderRules, ObjectSse, Progress, SItem, SItemsCache};
+use crate::utils::md5::file_matches_etag;
 
 // region:    --- Upload/Download Types
 
@@ -18,11 +33,20 @@ pub enum OverMode {
 	Skip,
 
 	/// Etag (only if different etag)
-	/// NOTE: Does not support multi-part etag. Just assume simple md5 etag
+	/// NOTE: Multipart etags are compared for the ss3 part size and the common part sizes (see `file_matches_etag`)
 	Etag,
 
 	/// Fail if exists.
 	Fail,
+
+	/// Only if different size (no hashing, so fast on big trees)
+	Size,
+
+	/// Only if the source is more recent than the destination
+	Newer,
+
+	/// Only if different size or the source is more recent than the destination
+	SizeMtime,
 }
 
 impl OverMode {
@@ -32,8 +56,21 @@ impl OverMode {
 			OverMode::Skip => "Exists",
 			OverMode::Etag => "Etag",
 			OverMode::Fail => "Fail",
+			OverMode::Size => "Size",
+			OverMode::Newer => "Newer",
+			OverMode::SizeMtime => "Size-Mtime",
 		}
 	}
+
+	/// Returns true if the mode compares with the existing destination item (so, a listing cache of the destination is worth it)
+	pub fn compares_dst(&self) -> bool {
+		matches!(self, OverMode::Etag | OverMode::Size | OverMode::Newer | OverMode::SizeMtime)
+	}
+
+	/// Returns true if the mode compares the uploaded content (etag or size), so the compressed one when compressed
+	pub fn compares_content(&self) -> bool {
+		matches!(self, OverMode::Etag | OverMode::Size | OverMode::SizeMtime)
+	}
 }
 
 #[derive(Default, Clone)]
@@ -43,16 +80,196 @@ pub struct CpOptions {
 	pub includes: Option<GlobSet>,
 	pub over: OverMode,
 	pub show_skip: bool,
-	/// File with no extension content type
-	pub noext_ct: Option<String>,
+	/// Content type of the uploads (overrides the one from the file extension)
+	pub content_type: Option<String>,
+	/// Headers and user metadata of the uploaded/copied objects
+	pub headers: ObjectHeaders,
+	/// Per glob content type and headers of the uploads, over the ones above (`--headers-file`, `--noext-ct`)
+	pub header_rules: HeaderRules,
+	/// Compression of the uploaded files (with their `Content-Encoding`), when it reduces their size
+	pub compress: Option<CompressMode>,
+	/// Client side encryption of the uploaded files (the key is also used to decrypt the encrypted objects downloaded)
+	pub encrypt_key: Option<EncryptKey>,
+	/// Server side encryption of the uploaded/copied objects (the SSE-C key is also used to read them)
+	pub sse: ObjectSse,
+	pub multipart: MultipartOptions,
+	/// Max number of files/objects transferred at the same time (0 or 1 for one at a time)
+	pub concurrency: usize,
+	/// Only report what would be transferred (only the read-only list/head requests are done)
+	pub dry_run: bool,
+	/// Progress of the transfers (hidden by default)
+	pub progress: Progress,
+}
+
+impl CpOptions {
+	pub fn concurrency(&self) -> usize {
+		self.concurrency.max(1)
+	}
+}
+
+/// Multipart options, used for both the multipart uploads and the ranged downloads
+#[derive(Debug, Clone)]
+pub struct MultipartOptions {
+	/// Files/objects with a size greater or equal to this threshold are transferred by parts
+	pub threshold: u64,
+	/// The requested part size (might be increased to stay within the S3 max part count)
+	pub part_size: u64,
+	/// Max number of parts transferred at the same time for a given file/object
+	pub concurrency: usize,
+}
+
+impl Default for MultipartOptions {
+	fn default() -> Self {
+		MultipartOptions {
+			threshold: DEFAULT_MULTIPART_THRESHOLD,
+			part_size: DEFAULT_MULTIPART_PART_SIZE,
+			concurrency: DEFAULT_PART_CONCURRENCY,
+		}
+	}
+}
+
+impl MultipartOptions {
+	pub fn new(threshold: u64, part_size: u64, concurrency: usize) -> Result<Self> {
+		if !(S3_MIN_PART_SIZE..=S3_MAX_PART_SIZE).contains(&part_size) {
+			return Err(Error::InvalidPartSize(part_size));
+		}
+		Ok(MultipartOptions {
+			threshold,
+			part_size,
+			concurrency: concurrency.max(1),
+		})
+	}
+
+	pub fn is_multipart(&self, size: u64) -> bool {
+		size >= self.threshold
+	}
+
+	/// Returns the part size to use for a given total size.
+	/// Note: When the requested part size would exceed the S3 max part count, the part size is doubled until it fits.
+	pub fn part_size_for(&self, size: u64) -> u64 {
+		let mut part_size = self.part_size;
+		while size.div_ceil(part_size) > S3_MAX_PARTS && part_size < S3_MAX_PART_SIZE {
+			part_size = (part_size * 2).min(S3_MAX_PART_SIZE);
+		}
+		part_size
+	}
+}
+
+/// The headers and user metadata set on the uploaded/copied objects (e.g., `--cache-control`, `--metadata key=value`)
+///
+/// Note: For the server side copies, when any is set, they replace all of the source ones (metadata directive REPLACE).
+#[derive(Debug, Default, Clone)]
+pub struct ObjectHeaders {
+	pub cache_control: Option<String>,
+	pub content_encoding: Option<String>,
+	pub content_disposition: Option<String>,
+	pub content_language: Option<String>,
+	pub expires: Option<DateTime>,
+	/// The user metadata (stored as `x-amz-meta-{key}`)
+	pub metadata: Option<HashMap<String, String>>,
+	/// Note: Not part of the metadata replaced on copy (the storage class of a copy is set on its own)
+	pub storage_class: Option<StorageClass>,
+}
+
+impl ObjectHeaders {
+	/// Returns true if any of the headers/metadata replacing the source ones of a copy is set (all but the storage class)
+	pub fn replaces_metadata(&self) -> bool {
+		!(self.cache_control.is_none()
+			&& self.content_encoding.is_none()
+			&& self.content_disposition.is_none()
+			&& self.content_language.is_none()
+			&& self.expires.is_none()
+			&& self.metadata.is_none())
+	}
+
+	/// Returns the headers of a copy/relay of an object with the `src` headers.
+	/// As a server side copy, the given headers replace all of the source ones when any is set, otherwise the source ones are kept.
+	/// Note: The storage class is always the given one (not carried from the source).
+	pub(super) fn for_copy_of(&self, src: ObjectHeaders) -> ObjectHeaders {
+		if self.replaces_metadata() {
+			self.clone()
+		} else {
+			ObjectHeaders {
+				storage_class: self.storage_class.clone(),
+				..src
+			}
+		}
+	}
+}
+
+/// Implement the `from_...` of the object responses (the headers/metadata of a source object, without its storage class)
+macro_rules! impl_object_headers_from {
+	($($fn_name:ident: $output:ty),* $(,)?) => {
+		impl ObjectHeaders {
+			$(
+				pub(super) fn $fn_name(output: &$output) -> Self {
+					ObjectHeaders {
+						cache_control: output.cache_control().map(|v| v.to_string()),
+						content_encoding: output.content_encoding().map(|v| v.to_string()),
+						content_disposition: output.content_disposition().map(|v| v.to_string()),
+						content_language: output.content_language().map(|v| v.to_string()),
+						expires: output
+							.expires_string()
+							.and_then(|v| DateTime::from_str(v, DateTimeFormat::HttpDate).ok()),
+						metadata: output.metadata().cloned(),
+						storage_class: None,
+					}
+				}
+			)*
+		}
+	};
+}
+
+impl_object_headers_from!(
+	from_head: HeadObjectOutput,
+	from_get: GetObjectOutput,
+);
+
+/// Implement the `apply_to_...` of the request builders (the same setters on each of them)
+macro_rules! impl_apply_object_headers {
+	($($fn_name:ident: $builder:ty),* $(,)?) => {
+		impl ObjectHeaders {
+			$(
+				pub(super) fn $fn_name(&self, builder: $builder) -> $builder {
+					builder
+						.set_cache_control(self.cache_control.clone())
+						.set_content_encoding(self.content_encoding.clone())
+						.set_content_disposition(self.content_disposition.clone())
+						.set_content_language(self.content_language.clone())
+						.set_expires(self.expires)
+						.set_metadata(self.metadata.clone())
+						.set_storage_class(self.storage_class.clone())
+				}
+			)*
+		}
+	};
+}
+
+impl_apply_object_headers!(
+	apply_to_put: PutObjectFluentBuilder,
+	apply_to_create_multipart: CreateMultipartUploadFluentBuilder,
+	apply_to_copy: CopyObjectFluentBuilder,
+);
+
+/// Parse a storage class (case insensitive), e.g., "STANDARD_IA", "glacier_ir"
+pub fn parse_storage_class(val: &str) -> Result<StorageClass> {
+	let storage_class = StorageClass::from(val.trim().to_uppercase().as_str());
+	if StorageClass::values().contains(&storage_class.as_str()) {
+		Ok(storage_class)
+	} else {
+		Err(Error::InvalidStorageClass(val.to_string()))
+	}
 }
 
 // endregion: --- Upload/Download Types
 
+/// Note: `content_file` is the file of the uploaded content (e.g., the compressed one), for the etag and size comparisons,
+///       while the modification time is the one of `src_file`.
 pub(super) async fn validate_over_for_s3_dest(
 	sbucket: &SBucket,
 	key: &str,
 	src_file: &Path,
+	content_file: &Path,
 	opts: &CpOptions,
 	sitems_cache: Option<&SItemsCache>,
 ) -> Result<bool> {
@@ -61,13 +278,24 @@ pub(super) async fn validate_over_for_s3_dest(
 		OverMode::Write => Ok(true),
 
 		// if skip, then the opposite of the exists state
-		OverMode::Skip => Ok(!sbucket.exists(key).await),
+		OverMode::Skip => Ok(!sbucket.exists(key, &opts.sse).await),
 
-		OverMode::Etag => Ok(!check_has_and_same_etags(sbucket, key, src_file, sitems_cache).await),
+		OverMode::Etag => Ok(!check_has_and_same_etags(sbucket, key, content_file, &opts.multipart, sitems_cache).await),
+
+		OverMode::Size | OverMode::Newer | OverMode::SizeMtime => match get_s3_sitem(sbucket, key, sitems_cache).await {
+			Some(dst) => {
+				let src = SizeMtime {
+					size: fs::metadata(content_file)?.len(),
+					..SizeMtime::from_file(src_file)?
+				};
+				Ok(opts.over.is_changed(&src, &SizeMtime::from_sitem(&dst)))
+			}
+			None => Ok(true),
+		},
 
 		// if fail mode, then if exists fail with error
 		OverMode::Fail => {
-			if sbucket.exists(key).await {
+			if sbucket.exists(key, &opts.sse).await {
 				Err(Error::ObjectExistsOverFailMode(format!("s3://{}/{key}", sbucket.name)))
 			} else {
 				Ok(true)
@@ -76,7 +304,8 @@ pub(super) async fn validate_over_for_s3_dest(
 	}
 }
 
-pub(super) fn validate_over_for_file_dest(file: &Path, opts: &CpOptions) -> Result<bool> {
+/// Validate the over mode for a download of `src` to `file` (the etag mode compares the md5 of the file with the object etag)
+pub(super) fn validate_over_for_file_dest(file: &Path, src: &SItem, opts: &CpOptions) -> Result<bool> {
 	match opts.over {
 		// if over: Write, then always true, we overwrite
 		OverMode::Write => Ok(true),
@@ -84,7 +313,12 @@ pub(super) fn validate_over_for_file_dest(file: &Path, opts: &CpOptions) -> Resu
 		// if skip, then the opposite of the exists state
 		OverMode::Skip => Ok(!file.exists()),
 
-		OverMode::Etag => panic!("->> FATAL ERROR - '--over etag' download not implemented yet"),
+		OverMode::Etag => Ok(!check_file_has_same_etag(file, src.etag.as_deref(), &opts.multipart)),
+
+		OverMode::Size | OverMode::Newer | OverMode::SizeMtime => match file.is_file() {
+			true => Ok(opts.over.is_changed(&SizeMtime::from_sitem(src), &SizeMtime::from_file(file)?)),
+			false => Ok(true),
+		},
 
 		// if fail mode, then if exists fail with error
 		OverMode::Fail => {
@@ -97,24 +331,161 @@ pub(super) fn validate_over_for_file_dest(file: &Path, opts: &CpOptions) -> Resu
 	}
 }
 
-/// returns true if both s3 object and files has successful etag, and the etcat match
-async fn check_has_and_same_etags(sbucket: &SBucket, s3_key: &str, file: impl AsRef<Path>, sitems_cache: Option<&SItemsCache>) -> bool {
-	// -- Get from cache or from s3 server if not found in cache
-	// A little odd block, but necessary give ownership constraints.
-	let sitem = sitems_cache.and_then(|c| c.get(s3_key));
-	let sitem_owned = if sitem.is_none() {
-		sbucket.get_sitem(s3_key).await.ok()
-	} else {
-		None
-	};
-	let sitem = sitem.or(sitem_owned.as_ref());
+/// Validate the over mode for a server side copy of `src` to `sbucket` (the etag mode compares the source and destination etags)
+pub(super) async fn validate_over_for_s3_copy(
+	sbucket: &SBucket,
+	key: &str,
+	src: &SItem,
+	opts: &CpOptions,
+	sitems_cache: Option<&SItemsCache>,
+) -> Result<bool> {
+	match opts.over {
+		// if over: Write, then always true, we overwrite
+		OverMode::Write => Ok(true),
+
+		// if skip, then the opposite of the exists state
+		OverMode::Skip => Ok(!sbucket.exists(key, &opts.sse).await),
+
+		OverMode::Etag => {
+			let dst_etag = get_s3_sitem(sbucket, key, sitems_cache).await.and_then(|dst| dst.etag);
+			let same = matches!((src.etag.as_deref(), dst_etag.as_deref()), (Some(src_etag), Some(dst_etag)) if src_etag == dst_etag);
+			Ok(!same)
+		}
 
-	if let (Ok(file_etag), Some(s3_etag)) = (compute_md5(file), sitem.and_then(|i| i.etag.as_deref())) {
-		// We copy if the tags are different
-		file_etag == s3_etag
+		OverMode::Size | OverMode::Newer | OverMode::SizeMtime => match get_s3_sitem(sbucket, key, sitems_cache).await {
+			Some(dst) => Ok(opts.over.is_changed(&SizeMtime::from_sitem(src), &SizeMtime::from_sitem(&dst))),
+			None => Ok(true),
+		},
+
+		// if fail mode, then if exists fail with error
+		OverMode::Fail => {
+			if sbucket.exists(key, &opts.sse).await {
+				Err(Error::ObjectExistsOverFailMode(sbucket.s3_url(key)))
+			} else {
+				Ok(true)
+			}
+		}
 	}
+}
+
+/// returns true if both s3 object and files has successful etag, and the etcat match
+async fn check_has_and_same_etags(
+	sbucket: &SBucket,
+	s3_key: &str,
+	file: &Path,
+	multipart: &MultipartOptions,
+	sitems_cache: Option<&SItemsCache>,
+) -> bool {
+	let s3_etag = get_s3_sitem(sbucket, s3_key, sitems_cache).await.and_then(|sitem| sitem.etag);
+
 	// if no etag or object found, then, We do the copy
-	else {
-		false
+	s3_etag.is_some_and(|s3_etag| file_matches_s3_etag(file, &s3_etag, multipart))
+}
+
+/// returns true if the file exists and its md5 match the s3 etag (if no etag or no file, then false, we download)
+fn check_file_has_same_etag(file: &Path, s3_etag: Option<&str>, multipart: &MultipartOptions) -> bool {
+	match s3_etag {
+		Some(s3_etag) if file.is_file() => file_matches_s3_etag(file, s3_etag, multipart),
+		_ => false,
 	}
 }
+
+/// returns true if the file match the s3 etag (simple md5, or multipart etag trying the ss3 part size first)
+pub(super) fn file_matches_s3_etag(file: &Path, s3_etag: &str, multipart: &MultipartOptions) -> bool {
+	let part_sizes: Vec<u64> = fs::metadata(file)
+		.map(|meta| vec![multipart.part_size_for(meta.len())])
+		.unwrap_or_default();
+	file_matches_etag(file, s3_etag, &part_sizes).unwrap_or(false)
+}
+
+/// Get the sitem of a s3 object from the cache or from s3 server if not found in cache
+async fn get_s3_sitem(sbucket: &SBucket, s3_key: &str, sitems_cache: Option<&SItemsCache>) -> Option<SItem> {
+	match sitems_cache.and_then(|c| c.get(s3_key)) {
+		Some(sitem) => Some(sitem.clone()),
+		// Note: `get_sitem` lists by prefix, so make sure it is the exact key
+		None => sbucket.get_sitem(s3_key).await.ok().filter(|sitem| sitem.key == s3_key),
+	}
+}
+
+// region:    --- SizeMtime
+
+/// The size and modification time of a file or object (for the size/mtime over modes)
+struct SizeMtime {
+	size: u64,
+	modified: Option<SystemTime>,
+}
+
+impl SizeMtime {
+	fn from_file(file: &Path) -> Result<SizeMtime> {
+		let meta = fs::metadata(file)?;
+		Ok(SizeMtime {
+			size: meta.len(),
+			modified: meta.modified().ok(),
+		})
+	}
+
+	fn from_sitem(sitem: &SItem) -> SizeMtime {
+		SizeMtime {
+			size: sitem.size.max(0) as u64,
+			modified: sitem.last_modified.and_then(|dt| SystemTime::try_from(dt).ok()),
+		}
+	}
+}
+
+impl OverMode {
+	/// Returns true if the `src` should be transferred over the existing `dst` (for the size/mtime modes)
+	/// Note: When a modification time is unknown, the source is considered newer.
+	fn is_changed(&self, src: &SizeMtime, dst: &SizeMtime) -> bool {
+		let size_changed = src.size != dst.size;
+		let newer = match (src.modified, dst.modified) {
+			(Some(src_modified), Some(dst_modified)) => src_modified > dst_modified,
+			_ => true,
+		};
+
+		match self {
+			OverMode::Size => size_changed,
+			OverMode::Newer => newer,
+			OverMode::SizeMtime => size_changed || newer,
+			_ => true,
+		}
+	}
+}
+
+// endregion: --- SizeMtime
+
+/// The prefix taken as a "folder" (trailing '/' added, unless empty), so that `logs` does not also match `logs-old/...` or `logs.txt`.
+pub(super) fn folder_prefix(prefix: &str) -> String {
+	if prefix.is_empty() || prefix.ends_with('/') {
+		prefix.to_string()
+	} else {
+		format!("{prefix}/")
+	}
+}
+
+/// Build the `copy_source` of a copy request (`bucket/key`, with the key url encoded)
+pub(super) fn copy_source(bucket: &str, key: &str) -> String {
+	let mut encoded_key = String::with_capacity(key.len());
+	for byte in key.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded_key.push(byte as char),
+			_ => encoded_key.push_str(&format!("%{byte:02X}")),
+		}
+	}
+	format!("{bucket}/{encoded_key}")
+}
+
+/// Read the 256-bit key of a key file (`--sse-c-key`, `--encrypt-key-file`), of the raw 32 bytes, or of the base64 encoded key.
+/// Note: The errors never contain the key material.
+pub(super) fn read_256_bit_key_file(path: &Path) -> Result<[u8; 32]> {
+	let content = fs::read(path).map_err(|_| Error::InvalidKeyFile("cannot read the key file"))?;
+	let key = match content.len() {
+		32 => content,
+		_ => BASE64
+			.decode(String::from_utf8_lossy(&content).trim())
+			.map_err(|_| Error::InvalidKeyFile("not a raw nor a base64 key"))?,
+	};
+
+	key
+		.try_into()
+		.map_err(|_| Error::InvalidKeyFile("the key must be 256 bits (32 bytes)"))
+}
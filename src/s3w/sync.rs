@@ -0,0 +1,465 @@
+use super::{HeaderRules, Inex, ListOptions, ObjectSse, Progress, SBucket, SItem, compute_inex};
+use crate::Result;
+use crate::s3w::cp_report::{CpAction, CpItem, CpReport};
+use crate::s3w::support::{CpOptions, MultipartOptions, OverMode, file_matches_s3_etag};
+use futures::StreamExt;
+use globset::GlobSet;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+// region:    --- SyncOptions
+
+#[derive(Default, Clone)]
+pub struct SyncOptions {
+	/// Note: The include/exclude globs are matched against the path relative to the sync source/destination.
+	pub excludes: Option<GlobSet>,
+	pub includes: Option<GlobSet>,
+	/// Delete the destination entries that are not in the source (excluded entries are never deleted)
+	pub delete: bool,
+	pub show_skip: bool,
+	/// Per glob content type and headers of the uploads (`--headers-file`, `--noext-ct`)
+	pub header_rules: HeaderRules,
+	/// Server side encryption of the uploaded/copied objects (the SSE-C key is also used to read them)
+	pub sse: ObjectSse,
+	pub multipart: MultipartOptions,
+	/// Max number of files/objects transferred at the same time (0 or 1 for one at a time)
+	pub concurrency: usize,
+	/// Only report what would be transferred/deleted (only the read-only list/head requests are done)
+	pub dry_run: bool,
+	/// Progress of the transfers (hidden by default)
+	pub progress: Progress,
+}
+
+impl SyncOptions {
+	/// The cp options used to transfer the entries of the plan (the plan already decided what to overwrite)
+	fn cp_options(&self) -> CpOptions {
+		CpOptions {
+			recursive: true,
+			over: OverMode::Write,
+			show_skip: self.show_skip,
+			header_rules: self.header_rules.clone(),
+			sse: self.sse.clone(),
+			multipart: self.multipart.clone(),
+			concurrency: self.concurrency,
+			dry_run: self.dry_run,
+			progress: self.progress.clone(),
+			..Default::default()
+		}
+	}
+}
+
+// endregion: --- SyncOptions
+
+// region:    --- Sync Plan
+
+/// A file or object of one side of the sync, by path relative to the sync source/destination.
+enum SyncEntry {
+	File {
+		file: PathBuf,
+		size: u64,
+		modified: Option<SystemTime>,
+	},
+	Object(SItem),
+}
+
+type SyncEntries = BTreeMap<String, SyncEntry>;
+
+impl SyncEntry {
+	fn size(&self) -> u64 {
+		match self {
+			SyncEntry::File { size, .. } => *size,
+			SyncEntry::Object(sitem) => sitem.size.max(0) as u64,
+		}
+	}
+
+	fn modified(&self) -> Option<SystemTime> {
+		match self {
+			SyncEntry::File { modified, .. } => *modified,
+			SyncEntry::Object(sitem) => sitem.last_modified.and_then(|dt| SystemTime::try_from(dt).ok()),
+		}
+	}
+}
+
+enum SyncOp {
+	/// Not in the destination
+	Create,
+	/// Different from the destination
+	Update,
+	/// Same as the destination
+	Unchanged,
+	/// Only in the destination (and `--delete`)
+	Delete,
+	Exclude,
+	Ignore,
+}
+
+struct SyncPlanItem<'a> {
+	rel_path: &'a str,
+	op: SyncOp,
+	/// The source entry (None for a delete)
+	src: Option<&'a SyncEntry>,
+	/// The destination entry (None for a create)
+	dst: Option<&'a SyncEntry>,
+}
+
+/// Compute the sync plan from the source and destination entries.
+/// Items are in the relative path order, with the deletes last.
+fn compute_sync_plan<'a>(src_entries: &'a SyncEntries, dst_entries: &'a SyncEntries, opts: &SyncOptions) -> Vec<SyncPlanItem<'a>> {
+	let mut plan: Vec<SyncPlanItem> = Vec::new();
+
+	for (rel_path, src) in src_entries.iter() {
+		let dst = dst_entries.get(rel_path);
+		let op = match compute_inex(rel_path, &opts.includes, &opts.excludes) {
+			Inex::Include => match dst {
+				None => SyncOp::Create,
+				Some(dst) if is_changed(src, dst, &opts.multipart) => SyncOp::Update,
+				Some(_) => SyncOp::Unchanged,
+			},
+			Inex::ExcludeInExclude => SyncOp::Exclude,
+			Inex::ExcludeNotInInclude => SyncOp::Ignore,
+		};
+		plan.push(SyncPlanItem {
+			rel_path,
+			op,
+			src: Some(src),
+			dst,
+		});
+	}
+
+	if opts.delete {
+		for (rel_path, dst) in dst_entries.iter() {
+			if !src_entries.contains_key(rel_path) && matches!(compute_inex(rel_path, &opts.includes, &opts.excludes), Inex::Include) {
+				plan.push(SyncPlanItem {
+					rel_path,
+					op: SyncOp::Delete,
+					src: None,
+					dst: Some(dst),
+				});
+			}
+		}
+	}
+
+	plan
+}
+
+/// Returns true if the source entry needs to be transferred over the destination one.
+/// - Different sizes are always changed.
+/// - Then, when the etags can be compared (same etags, simple md5 etags, or file matching the object etag), they decide.
+/// - Otherwise, the source is changed only if more recent than the destination.
+///
+/// Note: The etags of the SSE-KMS and SSE-C objects are not their md5, so only equal etags decide for them.
+///       The listed objects are taken as md5 etags until their head tells otherwise (see `confirm_etag_updates`).
+fn is_changed(src: &SyncEntry, dst: &SyncEntry, multipart: &MultipartOptions) -> bool {
+	if src.size() != dst.size() {
+		return true;
+	}
+
+	let same_etags = match (src, dst) {
+		(SyncEntry::Object(src), SyncEntry::Object(dst)) => match (&src.etag, &dst.etag) {
+			(Some(src_etag), Some(dst_etag)) if src_etag == dst_etag => Some(true),
+			(Some(src_etag), Some(dst_etag)) if is_md5_object_etag(src, src_etag) && is_md5_object_etag(dst, dst_etag) => Some(false),
+			_ => None,
+		},
+		(SyncEntry::File { file, .. }, SyncEntry::Object(sitem)) | (SyncEntry::Object(sitem), SyncEntry::File { file, .. }) => {
+			match sitem.etag.as_deref() {
+				_ if sitem.etag_is_md5 == Some(false) => None,
+				Some(etag) if file_matches_s3_etag(file, etag, multipart) => Some(true),
+				Some(etag) if is_md5_etag(etag) => Some(false),
+				// multipart etag of an unknown part size, cannot conclude
+				_ => None,
+			}
+		}
+		(SyncEntry::File { .. }, SyncEntry::File { .. }) => None,
+	};
+
+	match same_etags {
+		Some(same) => !same,
+		None => matches!((src.modified(), dst.modified()), (Some(src_modified), Some(dst_modified)) if src_modified > dst_modified),
+	}
+}
+
+/// Note: Multipart etags (with '-') depend on the part size of the upload, so two different ones might still be the same content.
+fn is_md5_etag(etag: &str) -> bool {
+	!etag.contains('-')
+}
+
+/// Note: Unknown for the listed objects (taken as md5, until their head tells otherwise).
+fn is_md5_object_etag(sitem: &SItem, etag: &str) -> bool {
+	sitem.etag_is_md5 != Some(false) && is_md5_etag(etag)
+}
+
+// endregion: --- Sync Plan
+
+// region:    --- Sync Entries Listing
+
+/// List the files of a local directory (recursive) by relative path ('/' separated).
+/// Note: Non unicode file paths, and the default ignored file names (e.g., `.DS_Store`), are not part of the sync.
+fn list_file_entries(sbucket: &SBucket, dir: &Path) -> Result<SyncEntries> {
+	let mut entries = SyncEntries::new();
+	if !dir.exists() {
+		return Ok(entries);
+	}
+
+	for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+		let file = entry.path();
+		if !file.is_file() {
+			continue;
+		}
+		if let Some(file_name) = file.file_name().and_then(|f| f.to_str())
+			&& let Some(ignore_set) = &sbucket.default_ignore_upload_names
+			&& ignore_set.contains(file_name)
+		{
+			continue;
+		}
+		let Some(rel_path) = file.strip_prefix(dir).ok().and_then(|p| p.to_str()) else {
+			continue;
+		};
+
+		let metadata = file.metadata()?;
+		entries.insert(
+			rel_path.replace('\\', "/"),
+			SyncEntry::File {
+				file: file.to_path_buf(),
+				size: metadata.len(),
+				modified: metadata.modified().ok(),
+			},
+		);
+	}
+
+	Ok(entries)
+}
+
+/// List the objects of a prefix (recursive, all pages) by path relative to the prefix.
+/// Note: The "folder" objects (key ending with '/') are not part of the sync.
+async fn list_object_entries(sbucket: &SBucket, prefix: &str) -> Result<SyncEntries> {
+	let mut entries = SyncEntries::new();
+
+	for sitem in sbucket.list_all(prefix, ListOptions::new(true)).await? {
+		if sitem.key.ends_with('/') {
+			continue;
+		}
+		if let Some(rel_path) = sitem.key.strip_prefix(prefix) {
+			entries.insert(rel_path.to_string(), SyncEntry::Object(sitem));
+		}
+	}
+
+	Ok(entries)
+}
+
+/// Normalize a sync prefix as a "folder" (no leading '/', and a trailing '/' when not the bucket root)
+fn sync_prefix(prefix: &str) -> String {
+	let prefix = prefix.strip_prefix('/').unwrap_or(prefix);
+	if prefix.is_empty() || prefix.ends_with('/') {
+		prefix.to_string()
+	} else {
+		format!("{prefix}/")
+	}
+}
+
+// endregion: --- Sync Entries Listing
+
+// region:    --- Sync Plan Confirmation
+
+impl SBucket {
+	/// Confirm the updates of the same size with the head of their objects (of this bucket), as the listing does not tell
+	/// their encryption (e.g., the etags of a bucket default SSE-KMS are not md5, so they do not tell if changed).
+	async fn confirm_etag_updates<'a>(&self, mut plan: Vec<SyncPlanItem<'a>>, opts: &SyncOptions) -> Result<Vec<SyncPlanItem<'a>>> {
+		let to_confirm: Vec<usize> = plan
+			.iter()
+			.enumerate()
+			.filter(|(_, item)| match (&item.op, item.src, item.dst) {
+				(SyncOp::Update, Some(src), Some(dst)) => src.size() == dst.size() && (has_unknown_etag(src) || has_unknown_etag(dst)),
+				_ => false,
+			})
+			.map(|(idx, _)| idx)
+			.collect();
+
+		let plan_ref = &plan;
+		let unchanged: Vec<usize> = futures::stream::iter(to_confirm)
+			.map(|idx| async move {
+				let item = &plan_ref[idx];
+				let (Some(src), Some(dst)) = (item.src, item.dst) else {
+					return None;
+				};
+				// Note: A failing head keeps the update (the transfer reports the error)
+				let src_head = self.head_unknown_etag(src, opts).await.ok()?;
+				let dst_head = self.head_unknown_etag(dst, opts).await.ok()?;
+				let changed = is_changed(src_head.as_ref().unwrap_or(src), dst_head.as_ref().unwrap_or(dst), &opts.multipart);
+				(!changed).then_some(idx)
+			})
+			.buffer_unordered(opts.concurrency.max(1))
+			.filter_map(|idx| async move { idx })
+			.collect()
+			.await;
+
+		for idx in unchanged {
+			plan[idx].op = SyncOp::Unchanged;
+		}
+
+		Ok(plan)
+	}
+
+	/// Returns the entry from its head if it is a listed object (etag kind unknown), otherwise None
+	async fn head_unknown_etag(&self, entry: &SyncEntry, opts: &SyncOptions) -> Result<Option<SyncEntry>> {
+		match entry {
+			SyncEntry::Object(sitem) if has_unknown_etag(entry) => Ok(Some(SyncEntry::Object(self.head_sitem_sse(&sitem.key, &opts.sse).await?))),
+			_ => Ok(None),
+		}
+	}
+}
+
+fn has_unknown_etag(entry: &SyncEntry) -> bool {
+	matches!(entry, SyncEntry::Object(sitem) if sitem.etag_is_md5.is_none() && sitem.etag.as_deref().is_some_and(is_md5_etag))
+}
+
+// endregion: --- Sync Plan Confirmation
+
+/// "sync" Implementation
+impl SBucket {
+	/// Sync a local directory to a prefix of this bucket (only the new and changed files are uploaded).
+	pub async fn sync_upload(&self, src_dir: &Path, prefix: &str, opts: SyncOptions) -> Result<CpReport> {
+		let prefix = sync_prefix(prefix);
+		let src_entries = list_file_entries(self, src_dir)?;
+		let dst_entries = list_object_entries(self, &prefix).await?;
+		let plan = compute_sync_plan(&src_entries, &dst_entries, &opts);
+		let plan = self.confirm_etag_updates(plan, &opts).await?;
+
+		let cp_opts = opts.cp_options();
+		let cp_opts = &cp_opts;
+		let prefix = prefix.as_str();
+		let mut results = futures::stream::iter(plan)
+			.map(|item| async move {
+				let key = format!("{prefix}{}", item.rel_path);
+				let src = item.src.map(|src| sync_entry_url(self, src)).unwrap_or_default();
+				let dst = self.s3_url(&key);
+				let res = match (item.op, item.src) {
+					(SyncOp::Delete, _) if cp_opts.dry_run => Ok(dry_run_delete_item(item.dst, &src, &dst)),
+					(SyncOp::Create | SyncOp::Update, Some(SyncEntry::File { file, .. })) => self.upload_file(file, &key, cp_opts, None).await,
+					(SyncOp::Delete, _) => self.delete_object(&key).await.map(|_| CpItem::new(CpAction::Delete, &src, &dst)),
+					(op, _) => Ok(no_transfer_item(op, &src, &dst)),
+				};
+				(src, dst, res)
+			})
+			.buffered(opts.concurrency.max(1));
+
+		let mut report = CpReport::new(&opts.progress);
+		while let Some((src, dst, res)) = results.next().await {
+			report.add(src, dst, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+
+	/// Sync a prefix of this bucket to a local directory (only the new and changed objects are downloaded).
+	pub async fn sync_download(&self, prefix: &str, dst_dir: &Path, opts: SyncOptions) -> Result<CpReport> {
+		let prefix = sync_prefix(prefix);
+		let src_entries = list_object_entries(self, &prefix).await?;
+		let dst_entries = list_file_entries(self, dst_dir)?;
+		let plan = compute_sync_plan(&src_entries, &dst_entries, &opts);
+		let plan = self.confirm_etag_updates(plan, &opts).await?;
+
+		let cp_opts = opts.cp_options();
+		let cp_opts = &cp_opts;
+		let mut results = futures::stream::iter(plan)
+			.map(|item| async move {
+				let dst_file = dst_dir.join(item.rel_path);
+				let src = item.src.map(|src| sync_entry_url(self, src)).unwrap_or_default();
+				let dst = dst_file.to_string_lossy().to_string();
+				let res = match (item.op, item.src) {
+					(SyncOp::Delete, _) if cp_opts.dry_run => Ok(dry_run_delete_item(item.dst, &src, &dst)),
+					(SyncOp::Create | SyncOp::Update, Some(SyncEntry::Object(sitem))) => {
+						match dst_file.parent().filter(|_| !cp_opts.dry_run).map(fs::create_dir_all).transpose() {
+							Ok(_) => self.download_file(&sitem.key, &dst_file, cp_opts, Some(sitem)).await,
+							Err(err) => Err(err.into()),
+						}
+					}
+					(SyncOp::Delete, _) => fs::remove_file(&dst_file)
+						.map(|_| CpItem::new(CpAction::Delete, &src, &dst))
+						.map_err(Into::into),
+					(op, _) => Ok(no_transfer_item(op, &src, &dst)),
+				};
+				(src, dst, res)
+			})
+			.buffered(opts.concurrency.max(1));
+
+		let mut report = CpReport::new(&opts.progress);
+		while let Some((src, dst, res)) = results.next().await {
+			report.add(src, dst, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+
+	/// Sync a prefix of `src_bucket` to a prefix of this bucket (server side copy, or relay when different credentials/endpoints).
+	pub async fn sync_copy(&self, src_bucket: &SBucket, src_prefix: &str, prefix: &str, opts: SyncOptions) -> Result<CpReport> {
+		let (src_prefix, prefix) = (sync_prefix(src_prefix), sync_prefix(prefix));
+		let src_entries = list_object_entries(src_bucket, &src_prefix).await?;
+		let dst_entries = list_object_entries(self, &prefix).await?;
+		let plan = compute_sync_plan(&src_entries, &dst_entries, &opts);
+
+		let cp_opts = opts.cp_options();
+		let cp_opts = &cp_opts;
+		let prefix = prefix.as_str();
+		let mut results = futures::stream::iter(plan)
+			.map(|item| async move {
+				let key = format!("{prefix}{}", item.rel_path);
+				let src = item.src.map(|src| sync_entry_url(src_bucket, src)).unwrap_or_default();
+				let dst = self.s3_url(&key);
+				let res = match (item.op, item.src) {
+					(SyncOp::Delete, _) if cp_opts.dry_run => Ok(dry_run_delete_item(item.dst, &src, &dst)),
+					(SyncOp::Create | SyncOp::Update, Some(SyncEntry::Object(sitem))) => {
+						self.copy_object_from(src_bucket, sitem, &key, cp_opts, None).await
+					}
+					(SyncOp::Delete, _) => self.delete_object(&key).await.map(|_| CpItem::new(CpAction::Delete, &src, &dst)),
+					(op, _) => Ok(no_transfer_item(op, &src, &dst)),
+				};
+				(src, dst, res)
+			})
+			.buffered(opts.concurrency.max(1));
+
+		let mut report = CpReport::new(&opts.progress);
+		while let Some((src, dst, res)) = results.next().await {
+			report.add(src, dst, res, opts.show_skip);
+		}
+
+		report.into_result()
+	}
+}
+
+// region:    --- Support
+
+fn sync_entry_url(sbucket: &SBucket, entry: &SyncEntry) -> String {
+	match entry {
+		SyncEntry::File { file, .. } => file.to_string_lossy().to_string(),
+		SyncEntry::Object(sitem) => sbucket.s3_url(&sitem.key),
+	}
+}
+
+fn dry_run_delete_item(dst_entry: Option<&SyncEntry>, src: &str, dst: &str) -> CpItem {
+	let size = dst_entry.map(SyncEntry::size).unwrap_or_default();
+	CpItem::new(CpAction::DryRun { verb: "delete", size }, src, dst)
+}
+
+/// The report item of the plan items without transfer
+fn no_transfer_item(op: SyncOp, src: &str, dst: &str) -> CpItem {
+	let action = match op {
+		SyncOp::Unchanged => CpAction::Skip { reason: "Unchanged" },
+		SyncOp::Exclude => CpAction::Exclude,
+		// Note: Create/Update/Delete are always handled by the caller
+		SyncOp::Ignore | SyncOp::Create | SyncOp::Update | SyncOp::Delete => CpAction::Ignore,
+	};
+	CpItem::new(action, src, dst)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-sync.rs"]
+mod tests;
+
+// endregion: --- Tests
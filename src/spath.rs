@@ -8,11 +8,15 @@ use std::path::{Path, PathBuf};
 pub enum SPath {
 	S3(S3Url),
 	File(PathBuf),
+	/// `-` for stdin (as source) or stdout (as destination)
+	Std,
 }
 
 impl SPath {
 	pub fn from_str(path: &str) -> Result<SPath> {
-		if path.starts_with("s3://") {
+		if path == "-" {
+			Ok(SPath::Std)
+		} else if path.starts_with("s3://") {
 			Ok(SPath::S3(S3Url::from_url(path)?))
 		} else {
 			Ok(SPath::File(Path::new(path).to_path_buf()))
@@ -26,6 +30,7 @@ impl fmt::Display for SPath {
 		match self {
 			SPath::S3(s3_url) => write!(f, "{}", s3_url),
 			SPath::File(path) => write!(f, "{}", path.display()),
+			SPath::Std => write!(f, "-"),
 		}
 	}
 }
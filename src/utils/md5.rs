@@ -22,6 +22,94 @@ pub fn compute_md5(file_path: impl AsRef<Path>) -> Result<String, Error> {
 	Ok(format!("{:x}", result))
 }
 
+/// Part sizes (in MiB) commonly used by the S3 clients for multipart uploads (aws cli, sdks, consoles, ...)
+const COMMON_PART_SIZES_MIB: &[u64] = &[5, 8, 15, 16, 32, 50, 64, 100, 128, 256, 512];
+const MIB: u64 = 1024 * 1024;
+
+/// Compute the S3 multipart etag of a file for a given part size.
+/// - Format: `{md5 of the concatenated part md5 digests}-{part count}`
+pub fn compute_multipart_etag(file_path: impl AsRef<Path>, part_size: u64) -> Result<String, Error> {
+	let file_path = file_path.as_ref();
+	let file = fs::File::open(file_path).map_err(|err| Error::fail_md5(file_path, err))?;
+	let mut reader = io::BufReader::new(file);
+	let mut buffer = [0; 8192];
+
+	let mut part_digests: Vec<u8> = Vec::new();
+	let mut part_count = 0;
+	loop {
+		// -- compute the md5 of the next part
+		let mut part_reader = (&mut reader).take(part_size);
+		let mut context = Context::new();
+		let mut part_len = 0;
+		loop {
+			let bytes_read = part_reader.read(&mut buffer).map_err(|err| Error::fail_md5(file_path, err))?;
+			if bytes_read == 0 {
+				break;
+			}
+			part_len += bytes_read;
+			context.consume(&buffer[..bytes_read]);
+		}
+		if part_len == 0 {
+			break;
+		}
+		part_digests.extend_from_slice(&context.finalize().0);
+		part_count += 1;
+	}
+
+	Ok(format!("{:x}-{part_count}", md5::compute(&part_digests)))
+}
+
+/// Returns the part count of a multipart etag (e.g., `..-12`), or None if simple md5 etag.
+pub fn etag_part_count(etag: &str) -> Option<u64> {
+	etag.split_once('-').and_then(|(_, count)| count.parse().ok())
+}
+
+/// Returns true if the file content match the S3 etag.
+/// - Simple etag - compared with the md5 of the file.
+/// - Multipart etag - compared with the multipart etag of the file for each candidate part size
+///   giving the same part count (the `part_sizes` first, then the common ones, then the one inferred from the part count).
+pub fn file_matches_etag(file_path: impl AsRef<Path>, etag: &str, part_sizes: &[u64]) -> Result<bool, Error> {
+	let file_path = file_path.as_ref();
+
+	let Some(part_count) = etag_part_count(etag) else {
+		return Ok(compute_md5(file_path)? == etag);
+	};
+
+	let size = fs::metadata(file_path).map_err(|err| Error::fail_md5(file_path, err))?.len();
+	for part_size in part_size_candidates(size, part_count, part_sizes) {
+		if compute_multipart_etag(file_path, part_size)? == etag {
+			return Ok(true);
+		}
+	}
+
+	Ok(false)
+}
+
+/// Returns the part sizes (without duplicates) that would split `size` in `part_count` parts.
+fn part_size_candidates(size: u64, part_count: u64, part_sizes: &[u64]) -> Vec<u64> {
+	if part_count == 0 {
+		return Vec::new();
+	}
+
+	// the part size inferred from the part count (rounded to the MiB as most clients do)
+	let inferred = size.div_ceil(part_count);
+	let inferred_mib = inferred.div_ceil(MIB) * MIB;
+
+	let all_part_sizes = part_sizes
+		.iter()
+		.copied()
+		.chain(COMMON_PART_SIZES_MIB.iter().map(|mib| mib * MIB))
+		.chain([inferred_mib, inferred]);
+
+	let mut candidates: Vec<u64> = Vec::new();
+	for part_size in all_part_sizes {
+		if part_size > 0 && size.div_ceil(part_size) == part_count && !candidates.contains(&part_size) {
+			candidates.push(part_size);
+		}
+	}
+	candidates
+}
+
 // region:    --- Error
 
 #[derive(Debug)]
@@ -53,3 +141,11 @@ impl std::error::Error for Error {}
 // endregion: --- Error Boilerplate
 
 // endregion: --- Error
+
+// region:    --- Tests
+
+#[cfg(test)]
+#[path = "../_tests/test-md5.rs"]
+mod tests;
+
+// endregion: --- Tests
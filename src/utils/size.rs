@@ -0,0 +1,24 @@
+//! Human size parsing for the CLI arguments (e.g., `--part-size 16MB`)
+
+use crate::{Error, Result};
+
+/// Parse a size string into bytes.
+/// - Units are 1024 based, case insensitive, and the trailing 'B' / 'iB' is optional (e.g., `16MB`, `16M`, `16MiB`)
+/// - No unit means bytes (e.g., `1048576`)
+pub fn parse_size(val: &str) -> Result<u64> {
+	let val = val.trim();
+	let num_end = val.find(|c: char| !c.is_ascii_digit()).unwrap_or(val.len());
+	let (num, unit) = val.split_at(num_end);
+
+	let num: u64 = num.parse().map_err(|_| Error::InvalidSize(val.to_string()))?;
+
+	let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+		"" | "b" => 1,
+		"k" | "kb" | "kib" => 1024,
+		"m" | "mb" | "mib" => 1024 * 1024,
+		"g" | "gb" | "gib" => 1024 * 1024 * 1024,
+		_ => return Err(Error::InvalidSize(val.to_string())),
+	};
+
+	num.checked_mul(multiplier).ok_or_else(|| Error::InvalidSize(val.to_string()))
+}
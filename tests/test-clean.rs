@@ -3,9 +3,10 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 mod utils;
 
-use crate::utils::{copy_dir_all, delete_s3_folder};
+use crate::utils::{FILE_FIXTURE_01_DIR, copy_dir_all, delete_s3_folder};
 use simple_fs::list_files;
 use std::fs;
+use std::path::Path;
 use utils::{exec_ss3, list_s3_folder};
 
 const TEST_CLEAN_BUCKET: &str = "s3://test-clean-bucket";
@@ -33,6 +34,77 @@ fn test_clean_simple() -> Result<()> {
 	Ok(())
 }
 
+#[test]
+fn test_clean_reverse_exclude_txt() -> Result<()> {
+	let fx_s3_folder = "test_clean_reverse_exclude_txt_folder";
+	let fx_full_dir = "./tests-data/.tmp/test_clean_reverse_exclude_txt_full";
+	let s3_folder = init_s3_folder(fx_s3_folder)?;
+
+	// prep the full dir with extra local files (not in s3)
+	if Path::new(fx_full_dir).exists() {
+		fs::remove_dir_all(fx_full_dir)?;
+	}
+	copy_dir_all(LOCAL_TEST_FOLDER, fx_full_dir)?;
+	fs::write(Path::new(fx_full_dir).join("extra-file.md"), "extra")?;
+	fs::write(Path::new(fx_full_dir).join("extra-file.txt"), "extra")?;
+
+	// EXEC - delete the local files not in s3, except the txt ones
+	let (success, out) = exec_ss3("clean", &[fx_full_dir, &s3_folder, "--reverse", "-e", "*.txt", "--force"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{out}");
+	assert!(
+		!Path::new(fx_full_dir).join("extra-file.md").exists(),
+		"Should delete 'extra-file.md'"
+	);
+	assert!(
+		Path::new(fx_full_dir).join("extra-file.txt").exists(),
+		"Should keep the excluded 'extra-file.txt'"
+	);
+	let files = list_files(fx_full_dir, Some(&["**/*"]), None)?;
+	assert_eq!(files.len(), 5, "Should keep the 4 fixture files and the excluded one");
+	let (count, _out) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 4, "Should not delete any s3 object");
+
+	// CLEAN
+	fs::remove_dir_all(fx_full_dir)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_clean_keeps_sibling_prefixes() -> Result<()> {
+	// FIXTURE - objects with the folder name as prefix, but not in the folder (and without local files)
+	let s3_folder = init_s3_folder("test_clean_keeps_sibling_prefixes")?;
+	let s3_sibling_dir = format!("{TEST_CLEAN_BUCKET}/test_clean_keeps_sibling_prefixes-old/");
+	let s3_sibling_file = format!("{TEST_CLEAN_BUCKET}/test_clean_keeps_sibling_prefixes.txt");
+	let text_file = format!("{FILE_FIXTURE_01_DIR}some-text.txt");
+	exec_ss3("cp", &[&text_file, &s3_sibling_dir, "--over", "write"], false)?;
+	exec_ss3("cp", &[&text_file, &s3_sibling_file, "--over", "write"], false)?;
+
+	// EXEC - the folder without its trailing '/'
+	let s3_folder_no_slash = s3_folder.trim_end_matches('/');
+	let (success, out) = exec_ss3("clean", &[LOCAL_TEST_FOLDER, s3_folder_no_slash, "--force"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{out}");
+	let (count, out) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 4, "Should keep the 4 folder objects. Content:\n{out}");
+	let (count, out) = list_s3_folder(&s3_sibling_dir)?;
+	assert_eq!(count, 1, "Should keep the sibling folder object. Content:\n{out}");
+	let (_, ls_out) = exec_ss3("ls", &[&s3_sibling_file], false)?;
+	assert!(
+		ls_out.contains("test_clean_keeps_sibling_prefixes.txt"),
+		"Should keep the sibling file. Content:\n{ls_out}"
+	);
+
+	// CLEAN
+	exec_ss3("rm", &[&s3_sibling_dir, "-r", "--force"], false)?;
+	exec_ss3("rm", &[&s3_sibling_file], false)?;
+
+	Ok(())
+}
+
 // region:    --- Utils
 
 /// Initialize a S3 folder with the fixture-01 content
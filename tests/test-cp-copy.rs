@@ -0,0 +1,98 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+mod utils;
+
+use utils::{S3_FIXTURE_01_DIR, create_bucket, delete_s3_folder, exec_ss3, get_bucket_from_s3_url, lazy_init_fixtures, list_s3_folder};
+
+const TEST_CP_COPY_BUCKET: &str = "s3://test-cp-copy-bucket";
+/// Note: This bucket has its own endpoint (see `tests/utils/exec.rs`), so the cp is a relay rather than a server side copy.
+const TEST_CP_RELAY_BUCKET: &str = "s3://test-cp-relay-bucket";
+
+#[test]
+fn test_cp_copy_key_to_key() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_COPY_BUCKET}/test_cp_copy_key_to_key/");
+	let src_url = format!("{S3_FIXTURE_01_DIR}image-01.jpg");
+	let dst_url = format!("{s3_base_dir}renamed-image.jpg");
+
+	// EXEC-CHECK-CLEAN
+	let (cp_out, ls_out) = base_tcc_cp_copy(&s3_base_dir, &[&src_url, &dst_url], 1)?;
+
+	// CHECK - additional check
+	assert!(cp_out.contains(&dst_url), "Should contain: {dst_url}\nbut contained: {cp_out}");
+	assert!(
+		ls_out.contains("renamed-image.jpg"),
+		"Should contain 'renamed-image.jpg'. Content:\n{ls_out}"
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_copy_dir_non_recursive() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_COPY_BUCKET}/test_cp_copy_dir_non_recursive/");
+
+	// EXEC-CHECK-CLEAN
+	base_tcc_cp_copy(&s3_base_dir, &[S3_FIXTURE_01_DIR, &s3_base_dir], 2)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_copy_dir_recursive_exclude_txt() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_COPY_BUCKET}/test_cp_copy_dir_recursive_exclude_txt/");
+	let args = &[S3_FIXTURE_01_DIR, &s3_base_dir, "-r", "-e", "*.txt", "--concurrency", "4"];
+
+	// EXEC-CHECK-CLEAN
+	let (_cp_out, ls_out) = base_tcc_cp_copy(&s3_base_dir, args, 1)?;
+
+	// CHECK - additional check
+	assert!(ls_out.contains("image-01.jpg"), "Should contain 'image-01.jpg'. Content:\n{ls_out}");
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_copy_relay_dir_recursive() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_RELAY_BUCKET}/test_cp_copy_relay_dir_recursive/");
+	let args = &[S3_FIXTURE_01_DIR, &s3_base_dir, "-r"];
+
+	// EXEC-CHECK-CLEAN
+	let (cp_out, _ls_out) = base_tcc_cp_copy(&s3_base_dir, args, 4)?;
+
+	// CHECK - additional check
+	assert!(cp_out.contains("Relaying"), "Should have been relayed. Content:\n{cp_out}");
+
+	Ok(())
+}
+
+// region:    --- Utils
+
+/// Base test-check-clean for the cp copy tests.
+/// - Exec the ss3 cp with the args (from the fixtures bucket)
+/// - Do the expected_count check with as ss3 ls -r from the s3_base_dir
+/// - Clean the s3_base_dir
+/// - Return the (cp_output, ls_ouput) tuple for additional check
+fn base_tcc_cp_copy(s3_base_dir: &str, args: &[&str], expected_count: usize) -> Result<(String, String)> {
+	lazy_init_fixtures()?;
+	create_bucket(&get_bucket_from_s3_url(s3_base_dir)?)?;
+
+	// EXEC
+	let (success, cp_out) = exec_ss3("cp", args, false)?;
+
+	// CHECK
+	assert!(success, "cp success was false!\n{cp_out}");
+	let (count, ls_out) = list_s3_folder(s3_base_dir)?;
+	assert_eq!(count, expected_count, "Should have {expected_count} objects in {s3_base_dir}");
+
+	// CLEAN
+	delete_s3_folder(s3_base_dir)?;
+
+	Ok((cp_out, ls_out))
+}
+
+// endregion: --- Utils
@@ -3,9 +3,9 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 mod utils;
 
-use std::fs::{create_dir_all, remove_dir_all};
+use std::fs::{create_dir_all, read, remove_dir_all, write};
 use std::path::{Path, PathBuf};
-use utils::{S3_FIXTURE_01_DIR, exec_ss3, lazy_init_fixtures};
+use utils::{FILE_FIXTURE_IMAGE_01, S3_FIXTURE_01_DIR, exec_ss3, lazy_init_fixtures};
 use walkdir::WalkDir;
 
 const TEST_CP_DOWNLOAD_BASE_DIR: &str = "./tests-data/.tmp/test-cp-download-base-dir/";
@@ -71,6 +71,172 @@ fn test_cp_download_recursive_exclude_txt() -> Result<()> {
 	Ok(())
 }
 
+#[test]
+fn test_cp_download_file_ranged() -> Result<()> {
+	let (dir_path, dir_str) = get_test_dir("test_cp_download_file_ranged");
+	let s3_image_url = format!("{S3_FIXTURE_01_DIR}image-01.jpg");
+
+	// EXEC-CHECK-CLEAN - threshold below the image size, so downloaded by range (and verified)
+	let files = base_tcc_cp_download(&dir_path, &[&s3_image_url, &dir_str, "--multipart-threshold", "1KB"], 1)?;
+
+	// CHECK - additional check
+	assert!(
+		files.contains("test_cp_download_file_ranged/image-01.jpg"),
+		"Does not contain 'image-01.jpg'. Content:\n{}",
+		files
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_download_over_etag() -> Result<()> {
+	// FIXTURE - first download, and change one of the files
+	let (dir_path, dir_str) = get_test_dir("test_cp_download_over_etag");
+	lazy_init_fixtures()?;
+	create_dir_all(&dir_path)?;
+	exec_ss3("cp", &[S3_FIXTURE_01_DIR, &dir_str, "-r"], false)?;
+	write(dir_path.join("some-text.txt"), "some changed text")?;
+
+	// EXEC
+	let (success, out) = exec_ss3("cp", &[S3_FIXTURE_01_DIR, &dir_str, "-r", "--over", "etag", "--show-skip"], false)?;
+
+	// CHECK
+	assert!(success, "cp success was false!\n{out}");
+	assert_eq!(out.matches("Skip (Etag)").count(), 3, "Should skip 3 files. Content:\n{out}");
+	assert_eq!(
+		out.matches("Downloading").count(),
+		1,
+		"Should download only the changed file. Content:\n{out}"
+	);
+	assert!(out.contains("some-text.txt"), "Should download 'some-text.txt'. Content:\n{out}");
+
+	// CLEAN
+	safer_remove_dir_all(&dir_path)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_download_over_newer() -> Result<()> {
+	// FIXTURE - first download (so, the local files are newer than the objects)
+	let (dir_path, dir_str) = get_test_dir("test_cp_download_over_newer");
+	lazy_init_fixtures()?;
+	create_dir_all(&dir_path)?;
+	exec_ss3("cp", &[S3_FIXTURE_01_DIR, &dir_str, "-r"], false)?;
+
+	// EXEC
+	let (success, out) = exec_ss3("cp", &[S3_FIXTURE_01_DIR, &dir_str, "-r", "--over", "newer", "--show-skip"], false)?;
+
+	// CHECK
+	assert!(success, "cp success was false!\n{out}");
+	assert_eq!(out.matches("Skip (Newer)").count(), 4, "Should skip all files. Content:\n{out}");
+	assert!(!out.contains("Downloading"), "Should not download any file. Content:\n{out}");
+
+	// CLEAN
+	safer_remove_dir_all(&dir_path)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_download_archived_object() -> Result<()> {
+	// FIXTURE - an object uploaded as GLACIER (so, archived)
+	let (dir_path, dir_str) = get_test_dir("test_cp_download_archived_object");
+	let s3_url = "s3://test-fixtures/test_cp_download_archived_object/image-01.jpg";
+	lazy_init_fixtures()?;
+	let (success, out) = exec_ss3(
+		"cp",
+		&[FILE_FIXTURE_IMAGE_01, s3_url, "--over", "write", "--storage-class", "GLACIER"],
+		false,
+	)?;
+	assert!(success, "Upload should succeed. Content:\n{out}");
+	let (_, ls_out) = exec_ss3("ls", &[s3_url, "-l"], false)?;
+	assert!(ls_out.contains("GLACIER"), "Should list the GLACIER class. Content:\n{ls_out}");
+
+	// EXEC
+	let (success, out) = exec_ss3("cp", &[s3_url, &dir_str], false)?;
+
+	// CHECK
+	assert!(!success, "Should fail. Content:\n{out}");
+	assert!(
+		out.contains("is archived (storage class GLACIER)"),
+		"Should fail with the archived error. Content:\n{out}"
+	);
+
+	// CLEAN
+	exec_ss3("rm", &[s3_url], false)?;
+	if dir_path.exists() {
+		safer_remove_dir_all(&dir_path)?;
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_download_encrypted_object() -> Result<()> {
+	// FIXTURE - an object uploaded client side encrypted (with a key file of 32 raw bytes)
+	let (dir_path, dir_str) = get_test_dir("test_cp_download_encrypted_object");
+	let s3_url = "s3://test-fixtures/test_cp_download_encrypted_object/image-01.jpg";
+	lazy_init_fixtures()?;
+	create_dir_all(&dir_path)?;
+	let key_file = dir_path.join("test.key");
+	write(&key_file, (0..32).collect::<Vec<u8>>())?;
+	let key_file = key_file.to_string_lossy().to_string();
+	let (success, out) = exec_ss3(
+		"cp",
+		&[FILE_FIXTURE_IMAGE_01, s3_url, "--over", "write", "--encrypt-key-file", &key_file],
+		false,
+	)?;
+	assert!(success, "Upload should succeed. Content:\n{out}");
+	assert!(out.contains("client side encrypted"), "Should be encrypted. Content:\n{out}");
+
+	// EXEC & CHECK - without the key
+	let (success, out) = exec_ss3("cp", &[s3_url, &dir_str], false)?;
+	assert!(!success, "Should fail without the key. Content:\n{out}");
+	assert!(
+		out.contains("requires '--encrypt-key-file'"),
+		"Should require the key. Content:\n{out}"
+	);
+	assert!(!dir_path.join("image-01.jpg").exists(), "Should not download the encrypted content");
+
+	// EXEC & CHECK - the over modes comparing the content are rejected
+	let (success, out) = exec_ss3("cp", &[s3_url, &dir_str, "--encrypt-key-file", &key_file, "--over", "etag"], false)?;
+	assert!(!success, "Should fail with '--over etag'. Content:\n{out}");
+	assert!(
+		out.contains("cannot be used with '--over"),
+		"Should reject the over mode. Content:\n{out}"
+	);
+
+	// EXEC & CHECK - with the key, single stream and by ranges
+	for threshold in ["8MB", "1KB"] {
+		let (success, out) = exec_ss3(
+			"cp",
+			&[
+				s3_url,
+				&dir_str,
+				"--encrypt-key-file",
+				&key_file,
+				"--multipart-threshold",
+				threshold,
+			],
+			false,
+		)?;
+		assert!(success, "Download should succeed. Content:\n{out}");
+		assert_eq!(
+			read(dir_path.join("image-01.jpg"))?,
+			read(FILE_FIXTURE_IMAGE_01)?,
+			"Should be decrypted (threshold {threshold})"
+		);
+	}
+
+	// CLEAN
+	exec_ss3("rm", &[s3_url], false)?;
+	safer_remove_dir_all(&dir_path)?;
+
+	Ok(())
+}
+
 // region:    --- utils
 
 /// Base test-check-clean for the cp tests.
@@ -0,0 +1,47 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+mod utils;
+
+use utils::{create_bucket, delete_s3_folder, exec_ss3, exec_ss3_with_stdin};
+
+const TEST_CP_STDIO_BUCKET: &str = "s3://test-cp-stdio-bucket";
+
+#[test]
+fn test_cp_stdin_to_stdout() -> Result<()> {
+	// FIXTURE
+	let s3_dir = format!("{TEST_CP_STDIO_BUCKET}/test_cp_stdin_to_stdout/");
+	let s3_file = format!("{s3_dir}from-stdin.txt");
+	let content = "Some content from stdin\nsecond line\n";
+	create_bucket(TEST_CP_STDIO_BUCKET)?;
+	delete_s3_folder(&s3_dir)?;
+
+	// EXEC - upload from stdin, and download to stdout
+	let (success, up_out) = exec_ss3_with_stdin("cp", &["-", &s3_file, "--content-type", "text/plain"], content)?;
+	assert!(success, "cp from stdin success was false!\n{up_out}");
+	let (success, down_out) = exec_ss3("cp", &[&s3_file, "-"], false)?;
+
+	// CHECK
+	assert!(success, "cp to stdout success was false!\n{down_out}");
+	assert!(
+		up_out.contains("(content-type: text/plain)"),
+		"Should upload with the given content type. Content:\n{up_out}"
+	);
+	// Note: exec_ss3 trims the output
+	assert_eq!(down_out, content.trim(), "stdout should only be the object content");
+	// Note: No json/ndjson records on stdout after the object content
+	for output in ["json", "ndjson"] {
+		let (success, down_out) = exec_ss3("cp", &[&s3_file, "-", "--output", output], false)?;
+		assert!(success, "cp to stdout with --output {output} success was false!\n{down_out}");
+		assert_eq!(
+			down_out,
+			content.trim(),
+			"stdout should only be the object content (--output {output})"
+		);
+	}
+
+	// CLEAN
+	delete_s3_folder(&s3_dir)?;
+
+	Ok(())
+}
@@ -3,9 +3,12 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 mod utils;
 
-use utils::{FILE_FIXTURE_01_DIR, FILE_FIXTURE_IMAGE_01, XString, create_bucket, delete_s3_folder, exec_ss3};
+use std::fs;
+use std::path::Path;
+use utils::{FILE_FIXTURE_01_DIR, FILE_FIXTURE_IMAGE_01, XString, copy_dir_all, create_bucket, delete_s3_folder, exec_ss3};
 
 const TEST_CP_UPLOAD_BUCKET: &str = "s3://test-cp-upload-bucket";
+const TEST_CP_UPLOAD_BASE_DIR: &str = "./tests-data/.tmp/test-cp-upload-base-dir/";
 
 #[test]
 fn test_cp_upload_file_to_key() -> Result<()> {
@@ -91,6 +94,92 @@ fn test_cp_upload_dir_recursive_excludes_txt() -> Result<()> {
 	Ok(())
 }
 
+#[test]
+fn test_cp_upload_dir_recursive_concurrency() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_UPLOAD_BUCKET}/test_cp_upload_dir_recursive_concurrency/");
+	let args = &[FILE_FIXTURE_01_DIR, &s3_base_dir, "-r", "--concurrency", "4"];
+
+	// EXEC-CHECK-CLEAN
+	base_tcc_cp_upload(&s3_base_dir, args, 4)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_upload_file_multipart() -> Result<()> {
+	// FIXTURE - 11MB file, so 3 parts with a 5MB part size
+	let fx_dir = "./.test-data/test_cp_upload_file_multipart";
+	let fx_file = format!("{fx_dir}/big-file.bin");
+	fs::create_dir_all(fx_dir)?;
+	fs::write(&fx_file, vec![7u8; 11 * 1024 * 1024])?;
+	let s3_base_dir = format!("{TEST_CP_UPLOAD_BUCKET}/test_cp_upload_file_multipart/");
+	let args = &[&fx_file, &s3_base_dir, "--multipart-threshold", "5MB", "--part-size", "5MB"];
+
+	// EXEC-CHECK-CLEAN
+	let (cp_out, _ls_out) = base_tcc_cp_upload(&s3_base_dir, args, 1)?;
+
+	// CHECK - Additional check
+	assert!(cp_out.contains("big-file.bin"), "ss3 cp output should contain big-file.bin");
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_upload_dir_dry_run() -> Result<()> {
+	// FIXTURE
+	let s3_base_dir = format!("{TEST_CP_UPLOAD_BUCKET}/test_cp_upload_dir_dry_run/");
+	let args = &[FILE_FIXTURE_01_DIR, &s3_base_dir, "-r", "--dry-run"];
+
+	// EXEC-CHECK-CLEAN - nothing uploaded
+	let (cp_out, _ls_out) = base_tcc_cp_upload(&s3_base_dir, args, 0)?;
+
+	// CHECK - Additional check
+	assert_eq!(
+		cp_out.matches("Would upload").count(),
+		4,
+		"Should report the 4 would be uploads. Content:\n{cp_out}"
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_cp_upload_dir_over_size() -> Result<()> {
+	// FIXTURE - first upload, and change the size of one of the files
+	let local_dir = format!("{TEST_CP_UPLOAD_BASE_DIR}test_cp_upload_dir_over_size/");
+	let s3_base_dir = format!("{TEST_CP_UPLOAD_BUCKET}/test_cp_upload_dir_over_size/");
+	if Path::new(&local_dir).exists() {
+		fs::remove_dir_all(&local_dir)?;
+	}
+	copy_dir_all(FILE_FIXTURE_01_DIR, &local_dir)?;
+	create_bucket(TEST_CP_UPLOAD_BUCKET)?;
+	delete_s3_folder(&s3_base_dir)?;
+	exec_ss3("cp", &[&local_dir, &s3_base_dir, "-r"], false)?;
+	fs::write(Path::new(&local_dir).join("some-text.txt"), "some text with a different size")?;
+
+	// EXEC-CHECK-CLEAN
+	let args = &[local_dir.as_str(), &s3_base_dir, "-r", "--over", "size", "--show-skip"];
+	let (cp_out, _ls_out) = base_tcc_cp_upload(&s3_base_dir, args, 4)?;
+
+	// CHECK - Additional check
+	assert_eq!(cp_out.matches("Skip (Size)").count(), 3, "Should skip 3 files. Content:\n{cp_out}");
+	assert_eq!(
+		cp_out.matches("Uploading").count(),
+		1,
+		"Should upload only the resized file. Content:\n{cp_out}"
+	);
+	assert!(
+		cp_out.contains("some-text.txt"),
+		"Should upload 'some-text.txt'. Content:\n{cp_out}"
+	);
+
+	// CLEAN
+	fs::remove_dir_all(&local_dir)?;
+
+	Ok(())
+}
+
 // region:    --- Utils
 
 /// Base test-check-clean for the cp upload test.
@@ -99,3 +99,55 @@ fn test_ls_fixture_01_count_includes_multiple() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_ls_fixture_01_long_bytes() -> Result<()> {
+	// FIXTURE
+	lazy_init_fixtures()?;
+
+	// EXEC
+	let (_, out) = exec_ss3("ls", &[S3_FIXTURE_01_DIR, "-l", "--bytes"], true)?;
+
+	// CHECK
+	// NOTE: With non recursive, the "folder" is returned as a 'PRE' line.
+	assert_eq!(out.x_lines().count(), 3);
+	let line = out
+		.x_lines()
+		.find(|l| l.ends_with("some-text.txt"))
+		.ok_or("Should have a 'some-text.txt' line")?;
+	let cols: Vec<&str> = line.split_whitespace().collect();
+	// date, time, size, storage class, etag, owner, key
+	assert_eq!(cols.len(), 7, "Wrong long line: {line}");
+	assert_eq!(cols[2], "29", "Should have the size in bytes: {line}");
+	assert_eq!(cols[3], "STANDARD", "Should have the storage class: {line}");
+	assert_eq!(cols[4], "c86a995bb08bf8f4cc2acce461a32dd1", "Should have the etag: {line}");
+	assert!(out.x_lines().any(|l| l.contains("PRE") && l.ends_with("sub-dir/")));
+
+	Ok(())
+}
+
+#[test]
+fn test_ls_fixture_01_ndjson() -> Result<()> {
+	// FIXTURE
+	lazy_init_fixtures()?;
+
+	// EXEC
+	let (_, out) = exec_ss3("ls", &[S3_FIXTURE_01_DIR, "-r", "--info", "--output", "ndjson"], true)?;
+
+	// CHECK - one json record per line, the 4 objects then the info summary
+	let records: Vec<serde_json::Value> = out.x_lines().map(serde_json::from_str).collect::<core::result::Result<_, _>>()?;
+	assert_eq!(records.len(), 5, "Wrong records. Content:\n{out}");
+	let objects: Vec<&serde_json::Value> = records.iter().filter(|r| r["type"] == "object").collect();
+	assert_eq!(objects.len(), 4);
+	let text = objects
+		.iter()
+		.find(|o| o["key"] == "fixture-01/some-text.txt")
+		.ok_or("Should have 'some-text.txt'")?;
+	assert_eq!(text["size"], 29);
+	assert_eq!(text["etag"], "c86a995bb08bf8f4cc2acce461a32dd1");
+	let info = records.last().ok_or("Should have the info record")?;
+	assert_eq!(info["type"], "info");
+	assert_eq!(info["total_count"], 4);
+
+	Ok(())
+}
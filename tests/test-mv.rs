@@ -0,0 +1,99 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+mod utils;
+
+use std::fs;
+use std::path::Path;
+use utils::{FILE_FIXTURE_01_DIR, copy_dir_all, create_bucket, delete_s3_folder, exec_ss3, list_s3_folder, upload_fixture_01};
+
+const TEST_MV_BUCKET: &str = "s3://test-mv-bucket";
+const TEST_MV_BASE_DIR: &str = "./tests-data/.tmp/test-mv-base-dir/";
+
+#[test]
+fn test_mv_upload_dir_recursive_exclude_txt() -> Result<()> {
+	// FIXTURE
+	let local_dir = format!("{TEST_MV_BASE_DIR}test_mv_upload_dir_recursive_exclude_txt/");
+	let s3_base_dir = format!("{TEST_MV_BUCKET}/test_mv_upload_dir_recursive_exclude_txt/");
+	if Path::new(&local_dir).exists() {
+		fs::remove_dir_all(&local_dir)?;
+	}
+	copy_dir_all(FILE_FIXTURE_01_DIR, &local_dir)?;
+	create_bucket(TEST_MV_BUCKET)?;
+	delete_s3_folder(&s3_base_dir)?;
+
+	// EXEC
+	let (success, mv_out) = exec_ss3("mv", &[&local_dir, &s3_base_dir, "-r", "-e", "*.txt"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{mv_out}");
+	let (count, ls_out) = list_s3_folder(&s3_base_dir)?;
+	assert_eq!(count, 1, "Should have uploaded only the image. Content:\n{ls_out}");
+	assert!(
+		!Path::new(&local_dir).join("image-01.jpg").exists(),
+		"The uploaded 'image-01.jpg' should be deleted. Content:\n{mv_out}"
+	);
+	assert!(
+		Path::new(&local_dir).join("some-text.txt").exists(),
+		"The excluded 'some-text.txt' should be kept. Content:\n{mv_out}"
+	);
+	assert!(
+		Path::new(&local_dir).join("sub-dir/sub-file-01.txt").exists(),
+		"The excluded 'sub-dir/sub-file-01.txt' should be kept. Content:\n{mv_out}"
+	);
+
+	// CLEAN
+	delete_s3_folder(&s3_base_dir)?;
+	fs::remove_dir_all(&local_dir)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_mv_copy_then_download_dir_recursive() -> Result<()> {
+	// FIXTURE
+	let s3_src_dir = format!("{TEST_MV_BUCKET}/test_mv_copy_then_download_dir_recursive/src/");
+	let s3_dst_dir = format!("{TEST_MV_BUCKET}/test_mv_copy_then_download_dir_recursive/dst/");
+	let local_dir = format!("{TEST_MV_BASE_DIR}test_mv_copy_then_download_dir_recursive/");
+	if Path::new(&local_dir).exists() {
+		fs::remove_dir_all(&local_dir)?;
+	}
+	create_bucket(TEST_MV_BUCKET)?;
+	delete_s3_folder(&format!("{TEST_MV_BUCKET}/test_mv_copy_then_download_dir_recursive/"))?;
+	upload_fixture_01(&s3_src_dir)?;
+
+	// EXEC - s3 to s3
+	let (success, mv_out) = exec_ss3("mv", &[&s3_src_dir, &s3_dst_dir, "-r"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{mv_out}");
+	assert_eq!(mv_out.matches("Deleting").count(), 4, "Should delete 4 sources. Content:\n{mv_out}");
+	let (count, ls_out) = list_s3_folder(&s3_dst_dir)?;
+	assert_eq!(count, 4, "Should have copied 4 objects. Content:\n{ls_out}");
+	let (_, ls_out) = exec_ss3("ls", &[&s3_src_dir, "-r"], false)?;
+	assert!(ls_out.trim().is_empty(), "The source objects should be deleted. Content:\n{ls_out}");
+
+	// EXEC - s3 to local
+	let (success, mv_out) = exec_ss3("mv", &[&s3_dst_dir, &local_dir, "-r"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{mv_out}");
+	assert!(
+		Path::new(&local_dir).join("image-01.jpg").exists(),
+		"Should download 'image-01.jpg'"
+	);
+	assert!(
+		Path::new(&local_dir).join("sub-dir/sub-file-02.txt").exists(),
+		"Should download 'sub-dir/sub-file-02.txt'"
+	);
+	let (_, ls_out) = exec_ss3("ls", &[&s3_dst_dir, "-r"], false)?;
+	assert!(
+		ls_out.trim().is_empty(),
+		"The downloaded objects should be deleted. Content:\n{ls_out}"
+	);
+
+	// CLEAN
+	fs::remove_dir_all(&local_dir)?;
+
+	Ok(())
+}
@@ -3,7 +3,7 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 mod utils;
 
-use crate::utils::{FILE_FIXTURE_IMAGE_01, XString, exec_ss3, get_test_bucket};
+use crate::utils::{FILE_FIXTURE_01_DIR, FILE_FIXTURE_IMAGE_01, XString, exec_ss3, exec_ss3_with_stdin, get_test_bucket};
 
 #[test]
 fn test_rb_success_empty() -> Result<()> {
@@ -47,3 +47,49 @@ fn test_rb_fail_not_empty() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_rb_force_not_empty() -> Result<()> {
+	// FIXTURE - rb bucket with some content.
+	let (bucket_url, bucket_name) = get_test_bucket("test_rb_force_not_empty");
+	exec_ss3("mb", &[&bucket_url], false)?;
+	exec_ss3("cp", &[FILE_FIXTURE_01_DIR, &bucket_url, "-r"], false)?;
+
+	// EXEC
+	let (success, out) = exec_ss3_with_stdin("rb", &[&bucket_url, "--force"], "YES\n")?;
+
+	// CHECK
+	assert!(success, "rb --force should be success=true. Cause: {out}");
+	assert!(out.contains("4 object versions"), "Should prompt with the counts. Out:\n{out}");
+	assert_eq!(out.matches("Deleted ").count(), 4, "Should delete the 4 objects. Out:\n{out}");
+	assert!(
+		out.contains(&format!("Bucket Deleted: {bucket_name}")),
+		"Should delete the bucket. Out:\n{out}"
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_rb_force_not_confirmed() -> Result<()> {
+	// FIXTURE - rb bucket with some content.
+	let (bucket_url, _bucket_name) = get_test_bucket("test_rb_force_not_confirmed");
+	exec_ss3("mb", &[&bucket_url], false)?;
+	exec_ss3("cp", &[FILE_FIXTURE_01_DIR, &bucket_url, "-r"], false)?;
+
+	// EXEC - not interactive, and no 'YES' on stdin (e.g., a script)
+	let (success, out) = exec_ss3("rb", &[&bucket_url, "--force"], false)?;
+
+	// CHECK - Should fail, and leave the bucket intact.
+	assert!(!success, "rb --force not confirmed should be success=false. Out:\n{out}");
+	assert!(out.contains("not confirmed"), "Should tell it was not confirmed. Out:\n{out}");
+	let (success, ls_out) = exec_ss3("ls", &[&bucket_url, "-r"], false)?;
+	assert!(success, "ls should be success. Cause: {ls_out}");
+	assert_eq!(ls_out.lines().count(), 4, "Should still have the 4 objects. Out:\n{ls_out}");
+
+	// CLEAN
+	let (success, _) = exec_ss3_with_stdin("rb", &[&bucket_url, "--force"], "YES\n")?;
+	assert!(success, "rb test bucket should be success.");
+
+	Ok(())
+}
@@ -0,0 +1,65 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+mod utils;
+
+use utils::{FILE_FIXTURE_01_DIR, FILE_FIXTURE_IMAGE_01, exec_ss3, lazy_init_fixtures};
+
+const S3_TEST_RESTORE_DIR: &str = "s3://test-fixtures/test_restore/";
+
+#[test]
+fn test_restore_recursive() -> Result<()> {
+	// FIXTURE - an archived (GLACIER) image, and a standard text file
+	lazy_init_fixtures()?;
+	let s3_image = format!("{S3_TEST_RESTORE_DIR}image-01.jpg");
+	let s3_text = format!("{S3_TEST_RESTORE_DIR}some-text.txt");
+	exec_ss3(
+		"cp",
+		&[FILE_FIXTURE_IMAGE_01, &s3_image, "--over", "write", "--storage-class", "GLACIER"],
+		false,
+	)?;
+	let text_file = format!("{FILE_FIXTURE_01_DIR}some-text.txt");
+	exec_ss3("cp", &[&text_file, &s3_text, "--over", "write"], false)?;
+
+	// EXEC
+	let (success, out) = exec_ss3("restore", &[S3_TEST_RESTORE_DIR, "-r", "--days", "2", "--tier", "Bulk"], false)?;
+
+	// CHECK - restore requested for the archived object only
+	assert!(success, "Restore should succeed. Content:\n{out}");
+	assert!(
+		out.contains(&format!("Restoring     {s3_image}   (days: 2, tier: Bulk)")),
+		"Should restore the image. Content:\n{out}"
+	);
+	assert!(
+		out.contains(&format!("Skip          {s3_text}   (not archived)")),
+		"Should skip the text file. Content:\n{out}"
+	);
+
+	// CHECK - the restore status with ls -l (the mock S3 restores right away)
+	let (_, ls_out) = exec_ss3("ls", &[S3_TEST_RESTORE_DIR, "-l"], false)?;
+	let image_line = ls_out
+		.lines()
+		.find(|line| line.contains("image-01.jpg"))
+		.ok_or("Should list the image")?;
+	assert!(
+		image_line.contains("(restored until "),
+		"Should show the restore status. Content:\n{ls_out}"
+	);
+
+	// CLEAN
+	exec_ss3("rm", &[S3_TEST_RESTORE_DIR, "-r", "--force"], false)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_restore_globs_require_recursive() -> Result<()> {
+	// EXEC
+	let (success, out) = exec_ss3("restore", &[S3_TEST_RESTORE_DIR, "-i", "*.jpg"], false)?;
+
+	// CHECK
+	assert!(!success, "Should fail. Content:\n{out}");
+	assert!(out.contains("require '-r'"), "Should require '-r'. Content:\n{out}");
+
+	Ok(())
+}
@@ -3,7 +3,7 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 mod utils;
 
-use utils::{exec_ss3, list_s3_folder, upload_fixture_01};
+use utils::{FILE_FIXTURE_01_DIR, exec_ss3, list_s3_folder, upload_fixture_01};
 
 const TEST_RM_BUCKET: &str = "s3://test-rm-bucket";
 
@@ -23,6 +23,117 @@ fn test_rm_single_key() -> Result<()> {
 	Ok(())
 }
 
+#[test]
+fn test_rm_single_key_dry_run() -> Result<()> {
+	let s3_folder = init_s3_folder("test_rm_single_key_dry_run")?;
+
+	// EXEC - would delete the image
+	let key_to_delete = format!("{s3_folder}image-01.jpg");
+	let (_, rm_out) = exec_ss3("rm", &[&key_to_delete, "--dry-run"], false)?;
+
+	// CHECK
+	assert!(rm_out.contains("Would delete"), "Should report 'Would delete'. Content:\n{rm_out}");
+	let (count, out) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 4, "Number of files in the {s3_folder}");
+	assert!(out.contains("image-01.jpg"), "Should still contain 'image-01.jpg'");
+
+	Ok(())
+}
+
+#[test]
+fn test_rm_recursive_exclude_jpg() -> Result<()> {
+	let s3_folder = init_s3_folder("test_rm_recursive_exclude_jpg")?;
+
+	// EXEC - delete all but the images
+	let (success, rm_out) = exec_ss3("rm", &[&s3_folder, "-r", "-e", "*.jpg", "--force"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{rm_out}");
+	assert_eq!(rm_out.matches("Deleted").count(), 3, "Should delete 3 objects. Content:\n{rm_out}");
+	let (count, out) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 1, "Number of files in the {s3_folder}");
+	assert!(out.contains("image-01.jpg"), "Should still contain 'image-01.jpg'");
+
+	Ok(())
+}
+
+#[test]
+fn test_rm_recursive_includes_dry_run() -> Result<()> {
+	let s3_folder = init_s3_folder("test_rm_recursive_includes_dry_run")?;
+
+	// EXEC - would delete the sub-dir files
+	let (_, rm_out) = exec_ss3("rm", &[&s3_folder, "-r", "-i", "**/sub-file-*", "--dry-run"], false)?;
+
+	// CHECK
+	assert_eq!(
+		rm_out.matches("Would delete").count(),
+		2,
+		"Should report 2 'Would delete'. Content:\n{rm_out}"
+	);
+	let (count, _) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 4, "Number of files in the {s3_folder}");
+
+	Ok(())
+}
+
+#[test]
+fn test_rm_recursive_json() -> Result<()> {
+	let s3_folder = init_s3_folder("test_rm_recursive_json")?;
+
+	// EXEC - delete all, with the json output (one array of events)
+	let (success, rm_out) = exec_ss3("rm", &[&s3_folder, "-r", "--force", "--output", "json"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{rm_out}");
+	let events: Vec<serde_json::Value> = serde_json::from_str(&rm_out)?;
+	assert_eq!(events.len(), 4, "Should have 4 events. Content:\n{rm_out}");
+	for event in events.iter() {
+		assert_eq!(event["type"], "event");
+		assert_eq!(event["action"], "delete");
+		assert_eq!(event["status"], "ok");
+	}
+	let (count, _) = list_s3_folder(&s3_folder)?;
+	assert_eq!(count, 1, "Should be empty (one empty line)");
+
+	Ok(())
+}
+
+#[test]
+fn test_rm_recursive_keeps_sibling_prefixes() -> Result<()> {
+	// FIXTURE - objects with the folder name as prefix, but not in the folder
+	let s3_folder = init_s3_folder("test_rm_recursive_keeps_sibling_prefixes")?;
+	let s3_sibling_dir = format!("{TEST_RM_BUCKET}/test_rm_recursive_keeps_sibling_prefixes-old/");
+	let s3_sibling_file = format!("{TEST_RM_BUCKET}/test_rm_recursive_keeps_sibling_prefixes.txt");
+	let text_file = format!("{FILE_FIXTURE_01_DIR}some-text.txt");
+	exec_ss3("cp", &[&text_file, &s3_sibling_dir, "--over", "write"], false)?;
+	exec_ss3("cp", &[&text_file, &s3_sibling_file, "--over", "write"], false)?;
+
+	// EXEC - the folder without its trailing '/'
+	let s3_folder_no_slash = s3_folder.trim_end_matches('/');
+	let (success, rm_out) = exec_ss3("rm", &[s3_folder_no_slash, "-r", "--force"], false)?;
+
+	// CHECK
+	assert!(success, "Should succeed. Content:\n{rm_out}");
+	assert_eq!(
+		rm_out.matches("Deleted").count(),
+		4,
+		"Should delete the 4 folder objects. Content:\n{rm_out}"
+	);
+	let (count, out) = list_s3_folder(&s3_sibling_dir)?;
+	assert_eq!(count, 1, "Should keep the sibling folder object. Content:\n{out}");
+	let (_, ls_out) = exec_ss3("ls", &[&s3_sibling_file], false)?;
+	assert!(
+		ls_out.contains("test_rm_recursive_keeps_sibling_prefixes.txt"),
+		"Should keep the sibling file. Content:\n{ls_out}"
+	);
+
+	// CLEAN
+	exec_ss3("rm", &[&s3_sibling_dir, "-r", "--force"], false)?;
+	exec_ss3("rm", &[&s3_sibling_file], false)?;
+
+	Ok(())
+}
+
 // region:    --- Utils
 
 /// Initialize a S3 folder with the fixture-01 content
@@ -0,0 +1,106 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+mod utils;
+
+use std::fs;
+use std::path::Path;
+use utils::{FILE_FIXTURE_01_DIR, copy_dir_all, create_bucket, delete_s3_folder, exec_ss3, list_s3_folder};
+
+const TEST_SYNC_BUCKET: &str = "s3://test-sync-bucket";
+const TEST_SYNC_BASE_DIR: &str = "./tests-data/.tmp/test-sync-base-dir/";
+
+#[test]
+fn test_sync_upload_only_changed() -> Result<()> {
+	// FIXTURE
+	let (local_dir, s3_dir) = init_sync_fixture("test_sync_upload_only_changed")?;
+	exec_ss3("sync", &[&local_dir, &s3_dir], false)?;
+	fs::write(Path::new(&local_dir).join("some-text.txt"), "some changed text")?;
+
+	// EXEC
+	let (success, out) = exec_ss3("sync", &[&local_dir, &s3_dir], false)?;
+
+	// CHECK
+	assert!(success, "sync success was false!\n{out}");
+	assert_eq!(
+		out.matches("Uploading").count(),
+		1,
+		"Should upload only the changed file. Content:\n{out}"
+	);
+	assert!(out.contains("some-text.txt"), "Should upload 'some-text.txt'. Content:\n{out}");
+
+	// CLEAN
+	clean_sync_fixture(&local_dir, &s3_dir)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_sync_upload_delete() -> Result<()> {
+	// FIXTURE
+	let (local_dir, s3_dir) = init_sync_fixture("test_sync_upload_delete")?;
+	exec_ss3("sync", &[&local_dir, &s3_dir], false)?;
+	fs::remove_file(Path::new(&local_dir).join("sub-dir/sub-file-02.txt"))?;
+
+	// EXEC
+	let (success, out) = exec_ss3("sync", &[&local_dir, &s3_dir, "--delete"], false)?;
+
+	// CHECK
+	assert!(success, "sync success was false!\n{out}");
+	let (count, ls_out) = list_s3_folder(&s3_dir)?;
+	assert_eq!(count, 3, "Should have 3 objects in {s3_dir}");
+	assert!(
+		!ls_out.contains("sub-file-02.txt"),
+		"Should not contain 'sub-file-02.txt'. Content:\n{ls_out}"
+	);
+
+	// CLEAN
+	clean_sync_fixture(&local_dir, &s3_dir)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_sync_upload_sse_kms_unchanged() -> Result<()> {
+	// FIXTURE - first sync (the SSE-KMS etags are not the md5 of the files)
+	let (local_dir, s3_dir) = init_sync_fixture("test_sync_upload_sse_kms_unchanged")?;
+	let (success, out) = exec_ss3("sync", &[&local_dir, &s3_dir, "--sse", "aws:kms"], false)?;
+	assert!(success, "First sync should succeed. Content:\n{out}");
+
+	// EXEC
+	let (success, out) = exec_ss3("sync", &[&local_dir, &s3_dir, "--sse", "aws:kms"], false)?;
+
+	// CHECK
+	assert!(success, "sync success was false!\n{out}");
+	assert!(!out.contains("Uploading"), "Should not upload the unchanged files. Content:\n{out}");
+
+	// CLEAN
+	clean_sync_fixture(&local_dir, &s3_dir)?;
+
+	Ok(())
+}
+
+// region:    --- Utils
+
+/// Returns the (local_dir, s3_dir) of the test, with the local_dir being a copy of the fixture-01 folder.
+fn init_sync_fixture(test_name: &str) -> Result<(String, String)> {
+	create_bucket(TEST_SYNC_BUCKET)?;
+
+	let local_dir = format!("{TEST_SYNC_BASE_DIR}{test_name}/");
+	let s3_dir = format!("{TEST_SYNC_BUCKET}/{test_name}/");
+	if Path::new(&local_dir).exists() {
+		fs::remove_dir_all(&local_dir)?;
+	}
+	copy_dir_all(FILE_FIXTURE_01_DIR, &local_dir)?;
+	delete_s3_folder(&s3_dir)?;
+
+	Ok((local_dir, s3_dir))
+}
+
+fn clean_sync_fixture(local_dir: &str, s3_dir: &str) -> Result<()> {
+	fs::remove_dir_all(local_dir)?;
+	delete_s3_folder(s3_dir)?;
+	Ok(())
+}
+
+// endregion: --- Utils
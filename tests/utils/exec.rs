@@ -2,13 +2,18 @@ pub type Result<T> = core::result::Result<T, Error>;
 pub type Error = Box<dyn std::error::Error>; // For early dev.
 
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
-const ENV_CREDS: [(&str, &str); 6] = [
+const ENV_CREDS: [(&str, &str); 9] = [
 	("SS3_BUCKET_my_bucket_KEY_ID", "minio"),
 	("SS3_BUCKET_my_bucket_KEY_SECRET", "miniominio"),
 	("SS3_BUCKET_my_bucket_ENDPOINT", "http://127.0.0.1:9000"),
+	// same server, but other endpoint (for the s3 to s3 relay tests)
+	("SS3_BUCKET_test_cp_relay_bucket_KEY_ID", "minio"),
+	("SS3_BUCKET_test_cp_relay_bucket_KEY_SECRET", "miniominio"),
+	("SS3_BUCKET_test_cp_relay_bucket_ENDPOINT", "http://localhost:9000"),
 	// as fallback
 	("AWS_ACCESS_KEY_ID", "minio"),
 	("AWS_SECRET_ACCESS_KEY", "miniominio"),
@@ -16,16 +21,33 @@ const ENV_CREDS: [(&str, &str); 6] = [
 ];
 
 pub fn exec_ss3(ss3_sub_cmd: &str, args: &[&str], print_exec: bool) -> Result<(bool, String)> {
-	let cmd_args = [&["run"], &[ss3_sub_cmd], args].concat();
-
-	let (success, out) = exec_output(
-		"cargo",
-		&cmd_args,
-		&ExecConfig {
+	exec_ss3_with_config(
+		ss3_sub_cmd,
+		args,
+		ExecConfig {
 			print_exec,
 			..ExecConfig::default()
 		},
-	)?;
+	)
+}
+
+/// Same as `exec_ss3` but with the `stdin` content given to the command (for the '-' paths)
+pub fn exec_ss3_with_stdin(ss3_sub_cmd: &str, args: &[&str], stdin: &str) -> Result<(bool, String)> {
+	exec_ss3_with_config(
+		ss3_sub_cmd,
+		args,
+		ExecConfig {
+			print_exec: false,
+			stdin: Some(stdin.to_string()),
+			..ExecConfig::default()
+		},
+	)
+}
+
+fn exec_ss3_with_config(ss3_sub_cmd: &str, args: &[&str], config: ExecConfig) -> Result<(bool, String)> {
+	let cmd_args = [&["run"], &[ss3_sub_cmd], args].concat();
+
+	let (success, out) = exec_output("cargo", &cmd_args, &config)?;
 	if !success {
 		// NOTE: If we have a mb and the error contains 'BucketAlreadyOwnedByYou' it's fine for test
 		if !(ss3_sub_cmd == "mb" && out.contains("BucketAlreadyOwnedByYou")) {
@@ -46,6 +68,7 @@ struct ExecConfig {
 	print_exec: bool,
 	cwd: Option<PathBuf>,
 	envs: Option<HashMap<&'static str, &'static str>>,
+	stdin: Option<String>,
 }
 
 impl Default for ExecConfig {
@@ -54,12 +77,18 @@ impl Default for ExecConfig {
 			print_exec: true,
 			cwd: None,
 			envs: Some(ENV_CREDS.into()),
+			stdin: None,
 		}
 	}
 }
 
 fn exec_output(cmd: &str, args: &[&str], config: &ExecConfig) -> Result<(bool, String)> {
-	let ExecConfig { print_exec, cwd, envs } = config;
+	let ExecConfig {
+		print_exec,
+		cwd,
+		envs,
+		stdin,
+	} = config;
 
 	if *print_exec {
 		println!("> executing: {} {}", cmd, args.join(" "));
@@ -78,7 +107,19 @@ fn exec_output(cmd: &str, args: &[&str], config: &ExecConfig) -> Result<(bool, S
 		}
 	}
 
-	match proc.stdout(Stdio::piped()).output() {
+	let output = match stdin {
+		Some(stdin) => {
+			let mut child = proc.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+			// Note: Dropping the stdin handle (end of the block) closes it, so that the command sees the end of the stream.
+			if let Some(mut child_stdin) = child.stdin.take() {
+				child_stdin.write_all(stdin.as_bytes())?;
+			}
+			child.wait_with_output()
+		}
+		None => proc.stdout(Stdio::piped()).output(),
+	};
+
+	match output {
 		Err(ex) => Err(ex)?,
 		Ok(output) => {
 			let success: bool;
@@ -9,7 +9,7 @@ pub type Error = Box<dyn std::error::Error>; // For early dev.
 mod exec;
 
 // --- Re-exports
-pub use exec::exec_ss3;
+pub use exec::{exec_ss3, exec_ss3_with_stdin};
 
 // --- Imports
 use std::path::Path;